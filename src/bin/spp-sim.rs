@@ -0,0 +1,149 @@
+use rand::rngs::StdRng;
+use rand::{thread_rng, Rng, SeedableRng};
+use single_player_poker::config::{self, Config, ConfigError};
+use single_player_poker::game::sim::{self, Report};
+use single_player_poker::game::strategy;
+use std::env;
+use std::process;
+
+const USAGE: &str = "\
+Usage: spp-sim [OPTIONS]
+
+Plays hands of the draw game without the terminal interface and reports
+how much they paid.
+
+Options:
+    --hands <N>             Hands to play (default: 10000)";
+
+#[derive(Debug, PartialEq)]
+struct Options {
+    hands: u64,
+    config: Config,
+}
+
+// Reads the simulator options and leaves the rest to the game config
+fn parse_args<I: Iterator<Item = String>>(mut args: I) -> Result<Options, ConfigError> {
+    let mut hands = 10_000;
+    let mut rest = vec![];
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--hands" => {
+                let value = args.next().ok_or_else(|| ConfigError::MissingValue(arg.clone()))?;
+                hands = value.parse().map_err(|_| ConfigError::InvalidValue(arg, value))?;
+            }
+            // The trainer and the daily challenge only make sense with
+            // a player
+            "--trainer" | "--daily" => return Err(ConfigError::UnknownArgument(arg)),
+            _ => rest.push(arg),
+        }
+    }
+
+    Ok(Options {
+        hands,
+        config: Config::from_args(rest.into_iter())?,
+    })
+}
+
+fn print_report(options: &Options, seed: u64, report: &Report) {
+    let config = &options.config;
+
+    println!("Hands:          {}", report.hands);
+    println!("Strategy:       {}", config.strategy);
+    println!("Pay table:      {}", config.pay_table.name);
+    println!("Rules:          {}", config.rules);
+    println!("Seed:           {}", seed);
+    println!();
+    println!("Total return:   {}", report.total);
+    println!("RTP:            {:.4}%", report.rtp() * 100.0);
+    println!("Variance:       {:.4}", report.variance());
+    println!("Hit frequency:  {:.4}%", report.win_frequency() * 100.0);
+    println!();
+    println!("{:<18}{:>6}{:>12}{:>12}", "Hand", "Pays", "Hits", "Frequency");
+
+    for category in config.rules.categories().into_iter().rev() {
+        if !config.rules.possible(category) {
+            continue;
+        }
+
+        println!(
+            "{:<18}{:>6}{:>12}{:>11.4}%",
+            category.name(),
+            config.pay_table.payout(category),
+            report.counts[category as usize],
+            report.hit_frequency(category) * 100.0
+        );
+    }
+}
+
+fn main() {
+    let options = parse_args(env::args().skip(1)).unwrap_or_else(|e| {
+        eprintln!("{}\n\n{}\n{}\n{}", e, USAGE, config::STRATEGY_OPTION, config::RULES_OPTIONS);
+        process::exit(2);
+    });
+
+    if options.config.show_help {
+        println!("{}\n{}\n{}", USAGE, config::STRATEGY_OPTION, config::RULES_OPTIONS);
+        return;
+    }
+
+    let config = &options.config;
+    let seed = config.seed.unwrap_or_else(|| thread_rng().gen());
+    let mut rng = StdRng::seed_from_u64(seed);
+    // The name was checked with the other options
+    let mut strategy = strategy::by_name(&config.strategy, &config.pay_table, seed.wrapping_add(1))
+        .expect("known strategy");
+
+    let result = sim::simulate(options.hands, &config.rules, &config.pay_table, &mut rng, &mut *strategy);
+
+    match result {
+        Ok(report) => print_report(&options, seed, &report),
+        Err(e) => {
+            eprintln!("Simulation error: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> impl Iterator<Item = String> + '_ {
+        s.split_whitespace().map(|a| a.to_string())
+    }
+
+    #[test]
+    fn defaults() {
+        let options = parse_args(args("")).unwrap();
+
+        assert_eq!(10_000, options.hands);
+        assert_eq!(Config::default(), options.config);
+    }
+
+    #[test]
+    fn sim_options() {
+        let options = parse_args(args("--hands 50 --wild 2 --strategy stand --seed 9")).unwrap();
+
+        assert_eq!(50, options.hands);
+        assert_eq!("stand", options.config.strategy);
+        assert_eq!(Some(9), options.config.seed);
+        assert!(options.config.rules.has_wilds());
+    }
+
+    #[test]
+    fn bad_options() {
+        assert_eq!(
+            Err(ConfigError::InvalidValue("--strategy".to_string(), "lucky".to_string())),
+            parse_args(args("--strategy lucky"))
+        );
+        assert_eq!(
+            Err(ConfigError::MissingValue("--hands".to_string())),
+            parse_args(args("--hands"))
+        );
+        assert_eq!(
+            Err(ConfigError::UnknownArgument("--trainer".to_string())),
+            parse_args(args("--trainer"))
+        );
+    }
+}
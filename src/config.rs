@@ -0,0 +1,355 @@
+use crate::game::paytable::{PayTable, PayTableError};
+use crate::game::rules::{Rules, Variant};
+use crate::game::strategy;
+use crate::{Rank, MAX_DECKS};
+use std::error::Error;
+use std::fmt;
+
+pub const USAGE: &str = "\
+Usage: single_player_poker [OPTIONS]
+
+Options:
+    --trainer               Grade every choice against the best one
+    --daily                 Play the daily challenge, the same 10 hands for everyone today with the standard rules and pay table";
+
+// Option shared by the programs that play hands on their own
+pub const STRATEGY_OPTION: &str = "\
+    --strategy <NAME>       Strategy playing on its own: stand, random, keep-pairs, chart or optimal (default: chart)";
+
+// Options for the rules, pay table and shuffles, shared by every
+// program
+pub const RULES_OPTIONS: &str = "\
+    --paytable <NAME|FILE>  Pay table preset or file to load (default: standard)
+    --jokers <N>            Jokers added to the deck, from 0 to 4 (default: 0)
+    --wild <RANK>           Rank of the wild cards, such as 2 (the default with --paytable deuces-wild)
+    --decks <N>             Decks shuffled together, from 1 to 8 (default: 1)
+    --short-deck            Play with the 36 card deck from 6 up (default pay table: short-deck)
+    --seed <N>              Seed for the shuffles to replay the same cards, a random one is picked otherwise
+    -h, --help              Print this help";
+
+// Options changing the rules or pay table
+const RULES_ARGS: [&str; 5] = ["--paytable", "--jokers", "--wild", "--decks", "--short-deck"];
+
+// Settings chosen when the game starts
+#[derive(Clone, Debug, PartialEq)]
+pub struct Config {
+    pub pay_table: PayTable,
+    pub rules: Rules,
+    pub trainer: bool,
+    // Daily challenge, dealt from a seed of today's date
+    pub daily: bool,
+    // Name of the strategy that plays on its own
+    pub strategy: String,
+    // Seed for the shuffles, picked at random when missing
+    pub seed: Option<u64>,
+    pub show_help: bool,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            pay_table: PayTable::default(),
+            rules: Rules::default(),
+            trainer: false,
+            daily: false,
+            strategy: "chart".to_string(),
+            seed: None,
+            show_help: false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConfigError {
+    UnknownArgument(String),
+    MissingValue(String),
+    InvalidValue(String, String),
+    // Two options that can't be used together
+    Conflict(String, String),
+    PayTable(PayTableError),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::UnknownArgument(arg) => write!(f, "Unknown argument \"{}\"", arg),
+            ConfigError::MissingValue(arg) => write!(f, "Missing value for {}", arg),
+            ConfigError::InvalidValue(arg, value) => {
+                write!(f, "Invalid value \"{}\" for {}", value, arg)
+            }
+            ConfigError::Conflict(a, b) => write!(f, "{} can't be used with {}", a, b),
+            ConfigError::PayTable(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for ConfigError {}
+
+impl Config {
+    // Reads the options from the command line arguments, without
+    // the program name
+    pub fn from_args<I: Iterator<Item = String>>(mut args: I) -> Result<Config, ConfigError> {
+        let mut config = Config::default();
+        let mut pay_table = None;
+        let mut wild = None;
+        // First option changing the rules or pay table
+        let mut rules_option = None;
+
+        while let Some(arg) = args.next() {
+            if RULES_ARGS.contains(&arg.as_str()) && rules_option.is_none() {
+                rules_option = Some(arg.clone());
+            }
+
+            match arg.as_str() {
+                "--paytable" => {
+                    let value = args.next().ok_or(ConfigError::MissingValue(arg))?;
+                    pay_table = Some(load_pay_table(&value)?);
+                }
+                "--jokers" => {
+                    let value = args.next().ok_or_else(|| ConfigError::MissingValue(arg.clone()))?;
+                    config.rules.jokers = value
+                        .parse()
+                        .ok()
+                        .filter(|n| *n <= 4)
+                        .ok_or(ConfigError::InvalidValue(arg, value))?;
+                }
+                "--decks" => {
+                    let value = args.next().ok_or_else(|| ConfigError::MissingValue(arg.clone()))?;
+                    config.rules.decks = value
+                        .parse()
+                        .ok()
+                        .filter(|n| (1..=MAX_DECKS).contains(n))
+                        .ok_or(ConfigError::InvalidValue(arg, value))?;
+                }
+                "--wild" => {
+                    let value = args.next().ok_or_else(|| ConfigError::MissingValue(arg.clone()))?;
+                    let rank = value
+                        .parse()
+                        .map_err(|_| ConfigError::InvalidValue(arg.clone(), value.clone()))?;
+                    config.rules.wild_rank = Some(rank);
+                    wild = Some((arg, value));
+                }
+                "--short-deck" => config.rules.variant = Variant::ShortDeck,
+                "--seed" => {
+                    let value = args.next().ok_or_else(|| ConfigError::MissingValue(arg.clone()))?;
+                    let seed = value
+                        .parse()
+                        .map_err(|_| ConfigError::InvalidValue(arg, value))?;
+                    config.seed = Some(seed);
+                }
+                "--trainer" => config.trainer = true,
+                "--daily" => config.daily = true,
+                "--strategy" => {
+                    let value = args.next().ok_or_else(|| ConfigError::MissingValue(arg.clone()))?;
+                    if !strategy::NAMES.contains(&value.as_str()) {
+                        return Err(ConfigError::InvalidValue(arg, value));
+                    }
+                    config.strategy = value;
+                }
+                "-h" | "--help" => config.show_help = true,
+                _ => return Err(ConfigError::UnknownArgument(arg)),
+            }
+        }
+
+        // The daily cards come from the date, and everyone plays them
+        // with the default rules and pay table so the scores compare
+        if config.daily && config.seed.is_some() {
+            return Err(ConfigError::Conflict("--daily".to_string(), "--seed".to_string()));
+        }
+        if let Some(arg) = rules_option.filter(|_| config.daily) {
+            return Err(ConfigError::Conflict("--daily".to_string(), arg));
+        }
+        // The trainer grades with the best choice, a hint the daily
+        // challenge doesn't give
+        if config.daily && config.trainer {
+            return Err(ConfigError::Conflict("--daily".to_string(), "--trainer".to_string()));
+        }
+
+        // Deuces wild pays for deuces being wild
+        if wild.is_none()
+            && pay_table == Some(PayTable::deuces_wild())
+            && config.rules.ranks().contains(&Rank::Two)
+        {
+            config.rules.wild_rank = Some(Rank::Two);
+        }
+
+        // The wild rank has to be in the deck
+        if let (Some(rank), Some((arg, value))) = (config.rules.wild_rank, wild) {
+            if !config.rules.ranks().contains(&rank) {
+                return Err(ConfigError::InvalidValue(arg, value));
+            }
+        }
+
+        config.pay_table = match (pay_table, config.rules.variant) {
+            (Some(table), _) => table,
+            (None, Variant::Standard) => PayTable::standard(),
+            (None, Variant::ShortDeck) => PayTable::short_deck(),
+        };
+
+        Ok(config)
+    }
+}
+
+// Presets are looked up by name before trying to load a file
+pub fn load_pay_table(name: &str) -> Result<PayTable, ConfigError> {
+    match PayTable::preset(name) {
+        Some(table) => Ok(table),
+        None => PayTable::load(name).map_err(ConfigError::PayTable),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> impl Iterator<Item = String> + '_ {
+        s.split_whitespace().map(|a| a.to_string())
+    }
+
+    #[test]
+    fn defaults() {
+        let config = Config::from_args(args("")).unwrap();
+
+        assert_eq!(PayTable::standard(), config.pay_table);
+        assert!(!config.trainer);
+        assert!(!config.show_help);
+
+        assert!(Config::from_args(args("--trainer")).unwrap().trainer);
+        assert_eq!("chart", config.strategy);
+    }
+
+    #[test]
+    fn strategy() {
+        let config = Config::from_args(args("--strategy keep-pairs")).unwrap();
+
+        assert_eq!("keep-pairs", config.strategy);
+        assert_eq!(
+            Err(ConfigError::InvalidValue("--strategy".to_string(), "lucky".to_string())),
+            Config::from_args(args("--strategy lucky"))
+        );
+    }
+
+    #[test]
+    fn pay_table_preset() {
+        let config = Config::from_args(args("--paytable jacks-or-better")).unwrap();
+
+        assert_eq!(PayTable::jacks_or_better(), config.pay_table);
+    }
+
+    #[test]
+    fn pay_table_file() {
+        let path = std::env::temp_dir().join(format!("spp_config_pay_table_{}.toml", std::process::id()));
+        std::fs::write(&path, "name = \"Test\"\nflush = 7\n").unwrap();
+
+        let config = Config::from_args(
+            vec!["--paytable".to_string(), path.to_string_lossy().to_string()].into_iter(),
+        )
+        .unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!("Test", config.pay_table.name);
+        assert!(Config::from_args(args("--paytable /no/such/file")).is_err());
+    }
+
+    #[test]
+    fn wild_cards() {
+        let config = Config::from_args(args("--jokers 2 --wild 2")).unwrap();
+
+        assert_eq!(2, config.rules.jokers);
+        assert_eq!(Some(crate::Rank::Two), config.rules.wild_rank);
+        assert_eq!(
+            Err(ConfigError::InvalidValue("--jokers".to_string(), "5".to_string())),
+            Config::from_args(args("--jokers 5"))
+        );
+        assert_eq!(
+            Err(ConfigError::InvalidValue("--wild".to_string(), "1".to_string())),
+            Config::from_args(args("--wild 1"))
+        );
+    }
+
+    #[test]
+    fn deuces_wild() {
+        let config = Config::from_args(args("--paytable deuces-wild")).unwrap();
+        assert_eq!(Some(Rank::Two), config.rules.wild_rank);
+
+        let config = Config::from_args(args("--paytable deuces-wild --wild 9")).unwrap();
+        assert_eq!(Some(Rank::Nine), config.rules.wild_rank);
+        assert_eq!(None, Config::from_args(args("--paytable standard")).unwrap().rules.wild_rank);
+    }
+
+    #[test]
+    fn short_deck() {
+        let config = Config::from_args(args("--short-deck")).unwrap();
+
+        assert_eq!(Variant::ShortDeck, config.rules.variant);
+        assert_eq!(PayTable::short_deck(), config.pay_table);
+
+        let config = Config::from_args(args("--short-deck --paytable standard --wild 6")).unwrap();
+        assert_eq!(PayTable::standard(), config.pay_table);
+
+        // There are no deuces in the short deck
+        assert_eq!(
+            Err(ConfigError::InvalidValue("--wild".to_string(), "2".to_string())),
+            Config::from_args(args("--wild 2 --short-deck"))
+        );
+    }
+
+    #[test]
+    fn decks() {
+        let config = Config::from_args(args("--decks 6")).unwrap();
+
+        assert_eq!(6, config.rules.decks);
+        assert_eq!(1, Config::from_args(args("")).unwrap().rules.decks);
+        assert_eq!(
+            Err(ConfigError::InvalidValue("--decks".to_string(), "0".to_string())),
+            Config::from_args(args("--decks 0"))
+        );
+        assert!(Config::from_args(args("--decks 9")).is_err());
+    }
+
+    #[test]
+    fn seed() {
+        assert_eq!(None, Config::from_args(args("")).unwrap().seed);
+        assert_eq!(Some(1234), Config::from_args(args("--seed 1234")).unwrap().seed);
+        assert_eq!(
+            Err(ConfigError::InvalidValue("--seed".to_string(), "-1".to_string())),
+            Config::from_args(args("--seed -1"))
+        );
+    }
+
+    #[test]
+    fn daily() {
+        let config = Config::from_args(args("--daily")).unwrap();
+        assert!(config.daily);
+        assert_eq!(Rules::default(), config.rules);
+        assert_eq!(PayTable::standard(), config.pay_table);
+
+        assert_eq!(
+            Err(ConfigError::Conflict("--daily".to_string(), "--seed".to_string())),
+            Config::from_args(args("--seed 3 --daily"))
+        );
+        assert_eq!(
+            Err(ConfigError::Conflict("--daily".to_string(), "--paytable".to_string())),
+            Config::from_args(args("--daily --paytable jacks-or-better"))
+        );
+        assert_eq!(
+            Err(ConfigError::Conflict("--daily".to_string(), "--trainer".to_string())),
+            Config::from_args(args("--trainer --daily"))
+        );
+        for rules in ["--jokers 1", "--wild 2", "--decks 2", "--short-deck"] {
+            assert!(Config::from_args(args(&format!("{} --daily", rules))).is_err());
+        }
+    }
+
+    #[test]
+    fn bad_arguments() {
+        assert_eq!(
+            Err(ConfigError::UnknownArgument("--fast".to_string())),
+            Config::from_args(args("--fast"))
+        );
+        assert_eq!(
+            Err(ConfigError::MissingValue("--paytable".to_string())),
+            Config::from_args(args("--paytable"))
+        );
+    }
+}
@@ -0,0 +1,184 @@
+use crate::date::Date;
+use crate::storage::{self, StorageError};
+use std::path::{Path, PathBuf};
+
+// Hands dealt in a daily challenge
+pub const DAILY_HANDS: u32 = 10;
+
+const FILE_NAME: &str = "daily.txt";
+
+// Mixed into the daily seeds so they aren't the plain splitmix64 of
+// the day number, which other programs might use too
+const SEED_SALT: u64 = 0x9e37_79b9_7f4a_7c15;
+
+// Seed of the day's shuffles. Everyone playing on the same day gets
+// the same cards. The seed is a hash of the date (splitmix64, which
+// stays the same on every platform and release). It isn't a secret,
+// anyone can work it out and play the day's cards with `--seed`.
+pub fn seed(date: Date) -> u64 {
+    let mut x = (date.days() as u64 ^ SEED_SALT).wrapping_mul(0xff51_afd7_ed55_8ccd);
+
+    for _ in 0..2 {
+        x = x.wrapping_add(SEED_SALT);
+        x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        x ^= x >> 31;
+    }
+
+    x
+}
+
+// How far the player got in the challenge of one day
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DailyResult {
+    pub date: Date,
+    pub score: i32,
+    pub hands: u32,
+}
+
+impl DailyResult {
+    pub fn is_finished(&self) -> bool {
+        self.hands >= DAILY_HANDS
+    }
+}
+
+// Results of every daily challenge played, kept in a text file with
+// one line per day:
+//
+//     # date score hands
+//     2024-03-09 42 10
+//
+// A day is recorded as soon as its first hand is dealt so it can't be
+// started over.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DailyResults {
+    results: Vec<DailyResult>,
+}
+
+impl DailyResults {
+    // File in the user data directory
+    pub fn default_path() -> Result<PathBuf, StorageError> {
+        Ok(storage::data_dir()?.join(FILE_NAME))
+    }
+
+    // No results yet if the file doesn't exist
+    pub fn load(path: &Path) -> Result<DailyResults, StorageError> {
+        match storage::read_file(path)? {
+            Some(contents) => DailyResults::parse(&contents),
+            None => Ok(DailyResults::default()),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), StorageError> {
+        storage::write_file(path, &self.to_file_string())
+    }
+
+    pub fn parse(contents: &str) -> Result<DailyResults, StorageError> {
+        let mut results = DailyResults::default();
+
+        for (n, line) in contents.lines().enumerate() {
+            let error = |message: String| StorageError::Parse { line: n + 1, message };
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let [date, score, hands] = fields[..] else {
+                return Err(error(format!("expected `date score hands`, got \"{}\"", line)));
+            };
+
+            results.record(DailyResult {
+                date: date.parse().map_err(|e| error(format!("{}", e)))?,
+                score: score.parse().map_err(|_| error(format!("invalid score \"{}\"", score)))?,
+                hands: hands.parse().map_err(|_| error(format!("invalid hands \"{}\"", hands)))?,
+            });
+        }
+
+        Ok(results)
+    }
+
+    pub fn to_file_string(&self) -> String {
+        let mut s = "# date score hands\n".to_string();
+
+        for result in &self.results {
+            s.push_str(&format!("{} {} {}\n", result.date, result.score, result.hands));
+        }
+
+        s
+    }
+
+    pub fn get(&self, date: Date) -> Option<&DailyResult> {
+        self.results.iter().find(|r| r.date == date)
+    }
+
+    // Adds the result of a day or replaces the one recorded before
+    pub fn record(&mut self, result: DailyResult) {
+        match self.results.iter_mut().find(|r| r.date == result.date) {
+            Some(old) => *old = result,
+            None => {
+                self.results.push(result);
+                self.results.sort_by_key(|r| r.date);
+            }
+        }
+    }
+
+    pub fn results(&self) -> &[DailyResult] {
+        &self.results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> Date {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn seeds() {
+        // Changing the seeds would change the cards of past days
+        assert_eq!(17010361685977357416, seed(date("2024-03-09")));
+        assert_ne!(seed(date("2024-03-09")), seed(date("2024-03-10")));
+    }
+
+    #[test]
+    fn record_days() {
+        let mut results = DailyResults::default();
+        let day = date("2024-03-09");
+
+        results.record(DailyResult { date: day, score: 0, hands: 0 });
+        results.record(DailyResult { date: date("2024-03-01"), score: 7, hands: 10 });
+        results.record(DailyResult { date: day, score: 12, hands: 4 });
+
+        assert_eq!(2, results.results().len());
+        assert_eq!(date("2024-03-01"), results.results()[0].date);
+        assert_eq!(Some(&DailyResult { date: day, score: 12, hands: 4 }), results.get(day));
+        assert!(!results.get(day).unwrap().is_finished());
+        assert!(results.get(date("2024-03-01")).unwrap().is_finished());
+        assert_eq!(None, results.get(date("2024-03-02")));
+    }
+
+    #[test]
+    fn file_round_trip() {
+        let results = DailyResults::parse("# date score hands\n2024-03-09 42 10\n\n2024-03-10 -3 2\n").unwrap();
+
+        assert_eq!(2, results.results().len());
+        assert_eq!(-3, results.get(date("2024-03-10")).unwrap().score);
+        assert_eq!(Ok(results.clone()), DailyResults::parse(&results.to_file_string()));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            Err(StorageError::Parse {
+                line: 2,
+                message: "invalid score \"lots\"".to_string()
+            }),
+            DailyResults::parse("2024-03-09 1 10\n2024-03-10 lots 10")
+        );
+        assert!(DailyResults::parse("2024-03-09 1").is_err());
+        assert!(DailyResults::parse("2024-02-30 1 10").is_err());
+    }
+}
@@ -0,0 +1,139 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Calendar date in UTC, written as 2024-03-09
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub year: i32,
+    pub month: u8,
+    pub day: u8,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseDateError(pub String);
+
+impl fmt::Display for ParseDateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid date \"{}\"", self.0)
+    }
+}
+
+impl Error for ParseDateError {}
+
+impl Date {
+    // None if the day doesn't exist
+    pub fn new(year: i32, month: u8, day: u8) -> Option<Date> {
+        let date = Date { year, month, day };
+        let days_in_month = match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if is_leap_year(year) => 29,
+            2 => 28,
+            _ => return None,
+        };
+
+        (1..=days_in_month).contains(&day).then_some(date)
+    }
+
+    pub fn today() -> Date {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        Date::from_days((seconds / 86_400) as i64)
+    }
+
+    // Date a number of days after 1970-01-01, from Howard Hinnant's
+    // civil_from_days
+    pub fn from_days(days: i64) -> Date {
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u8;
+        let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u8;
+        let year = (yoe + era * 400 + if month <= 2 { 1 } else { 0 }) as i32;
+
+        Date { year, month, day }
+    }
+
+    // Days since 1970-01-01
+    pub fn days(&self) -> i64 {
+        let year = self.year as i64 - if self.month <= 2 { 1 } else { 0 };
+        let era = year.div_euclid(400);
+        let yoe = year.rem_euclid(400);
+        let month = self.month as i64;
+        let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + self.day as i64 - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+
+        era * 146_097 + doe - 719_468
+    }
+}
+
+fn is_leap_year(year: i32) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl FromStr for Date {
+    type Err = ParseDateError;
+
+    fn from_str(s: &str) -> Result<Date, ParseDateError> {
+        let error = || ParseDateError(s.to_string());
+        let mut parts = s.trim().splitn(3, '-');
+        let mut next = || parts.next().and_then(|p| p.parse::<u32>().ok()).ok_or_else(error);
+
+        let (year, month, day) = (next()?, next()?, next()?);
+        if month > 12 || day > 31 {
+            return Err(error());
+        }
+
+        Date::new(year as i32, month as u8, day as u8).ok_or_else(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn days() {
+        assert_eq!(Date::new(1970, 1, 1).unwrap(), Date::from_days(0));
+        assert_eq!(Date::new(2000, 2, 29).unwrap(), Date::from_days(11_016));
+        assert_eq!(Date::new(2024, 3, 9).unwrap(), Date::from_days(19_791));
+        assert_eq!(Date::new(1969, 12, 31).unwrap(), Date::from_days(-1));
+
+        for days in -1000..30_000 {
+            assert_eq!(days, Date::from_days(days).days());
+        }
+    }
+
+    #[test]
+    fn invalid_dates() {
+        assert_eq!(None, Date::new(2023, 2, 29));
+        assert_eq!(None, Date::new(2024, 13, 1));
+        assert_eq!(None, Date::new(2024, 4, 31));
+        assert!(Date::new(2024, 2, 29).is_some());
+    }
+
+    #[test]
+    fn parse_and_format() {
+        let date = Date::new(2024, 3, 9).unwrap();
+
+        assert_eq!("2024-03-09", date.to_string());
+        assert_eq!(Ok(date), "2024-03-09".parse());
+        assert_eq!(Err(ParseDateError("2024-02-30".to_string())), "2024-02-30".parse::<Date>());
+        assert!("yesterday".parse::<Date>().is_err());
+        assert!("2024-03".parse::<Date>().is_err());
+    }
+}
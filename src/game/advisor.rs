@@ -0,0 +1,239 @@
+use crate::game::error::PokerError;
+use crate::game::hand::HandCategory;
+use crate::game::paytable::PayTable;
+use crate::game::poker::MAX_CHANGES;
+use crate::game::rules::Rules;
+use crate::Card;
+
+// Every way the draw can end for one choice of cards to change
+#[derive(Clone, Debug, PartialEq)]
+pub struct Outcomes {
+    pub to_change: Vec<usize>,
+    // Draws ending in each hand category, indexed by category
+    pub counts: [u64; HandCategory::ALL.len()],
+    // Points won over all the draws
+    pub points: i64,
+}
+
+impl Outcomes {
+    // Number of different draws
+    pub fn draws(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    // Points the choice is worth on average
+    pub fn expected(&self) -> f64 {
+        self.points as f64 / self.draws() as f64
+    }
+
+    pub fn probability(&self, category: HandCategory) -> f64 {
+        self.counts[category as usize] as f64 / self.draws() as f64
+    }
+}
+
+// Plays out every draw of the cards left in the deck after changing the
+// cards of the hand at the given indexes
+pub fn outcomes(
+    hand: &[Card],
+    to_change: &[usize],
+    remaining: &[Card],
+    rules: &Rules,
+    pay_table: &PayTable,
+) -> Result<Outcomes, PokerError> {
+    let mut cards: [Card; 5] = hand
+        .try_into()
+        .map_err(|_| PokerError::WrongHandSize(hand.len()))?;
+
+    if to_change.len() > MAX_CHANGES {
+        return Err(PokerError::TooManyChanges {
+            requested: to_change.len(),
+            max: MAX_CHANGES,
+        });
+    }
+    for (n, i) in to_change.iter().enumerate() {
+        if *i >= cards.len() {
+            return Err(PokerError::InvalidIndex(*i));
+        }
+        if to_change[..n].contains(i) {
+            return Err(PokerError::DuplicateIndex(*i));
+        }
+    }
+    if to_change.len() > remaining.len() {
+        return Err(PokerError::NotEnoughCards {
+            requested: to_change.len(),
+            remaining: remaining.len(),
+        });
+    }
+
+    let mut result = Outcomes {
+        to_change: to_change.to_vec(),
+        counts: [0; HandCategory::ALL.len()],
+        points: 0,
+    };
+
+    for_each_draw(remaining, to_change.len(), &mut |drawn| {
+        for (i, card) in to_change.iter().zip(drawn) {
+            cards[*i] = *card;
+        }
+
+        let value = rules.evaluate(&cards);
+        result.counts[value.category() as usize] += 1;
+        result.points += pay_table.points(value) as i64;
+    });
+
+    Ok(result)
+}
+
+// Every legal choice of cards to change, from the best expected
+// points to the worst
+pub fn rank_choices(
+    hand: &[Card],
+    remaining: &[Card],
+    rules: &Rules,
+    pay_table: &PayTable,
+) -> Result<Vec<Outcomes>, PokerError> {
+    let mut choices = vec![];
+
+    for bits in 0u32..1 << hand.len() {
+        if bits.count_ones() as usize > MAX_CHANGES.min(remaining.len()) {
+            continue;
+        }
+
+        let to_change: Vec<usize> = (0..hand.len()).filter(|i| bits & 1 << i != 0).collect();
+        choices.push(outcomes(hand, &to_change, remaining, rules, pay_table)?);
+    }
+
+    choices.sort_by(|a, b| b.expected().total_cmp(&a.expected()));
+
+    Ok(choices)
+}
+
+// The choice with the best expected points
+pub fn best_choice(
+    hand: &[Card],
+    remaining: &[Card],
+    rules: &Rules,
+    pay_table: &PayTable,
+) -> Result<Outcomes, PokerError> {
+    let choices = rank_choices(hand, remaining, rules, pay_table)?;
+
+    // Changing nothing is always a choice
+    Ok(choices.into_iter().next().expect("at least one choice"))
+}
+
+// Calls f with every set of n cards that can be drawn
+fn for_each_draw<F: FnMut(&[Card])>(cards: &[Card], n: usize, f: &mut F) {
+    let mut indexes: Vec<usize> = (0..n).collect();
+    let mut drawn: Vec<Card> = indexes.iter().map(|i| cards[*i]).collect();
+
+    loop {
+        f(&drawn);
+
+        // Moves the last index that can still move and resets the
+        // ones after it
+        let Some(pos) = (0..n).rev().find(|p| indexes[*p] < cards.len() - n + p) else {
+            return;
+        };
+
+        indexes[pos] += 1;
+        for p in pos + 1..n {
+            indexes[p] = indexes[p - 1] + 1;
+        }
+        for p in pos..n {
+            drawn[p] = cards[indexes[p]];
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::deck::Deck;
+    use crate::parse_hand;
+
+    // Deck with the hand already dealt
+    fn dealt(s: &str) -> (Vec<Card>, Deck) {
+        let hand = parse_hand(s).unwrap();
+        let cards = Deck::new()
+            .remaining()
+            .iter()
+            .filter(|c| !hand.contains(c))
+            .copied()
+            .collect();
+
+        (hand, Deck::from_cards(cards))
+    }
+
+    #[test]
+    fn draws() {
+        let mut count = 0;
+        for_each_draw(&parse_hand("As Ks Qs Js Ts 9s").unwrap(), 3, &mut |_| count += 1);
+        assert_eq!(20, count);
+
+        let mut count = 0;
+        for_each_draw(&[], 0, &mut |drawn| {
+            assert!(drawn.is_empty());
+            count += 1;
+        });
+        assert_eq!(1, count);
+    }
+
+    #[test]
+    fn standing_pat() {
+        let (hand, deck) = dealt("Ah Kh Qh Jh Th");
+        let table = PayTable::jacks_or_better();
+        let best = best_choice(&hand, deck.remaining(), &Rules::default(), &table).unwrap();
+
+        assert!(best.to_change.is_empty());
+        assert_eq!(1, best.draws());
+        assert_eq!(250.0, best.expected());
+    }
+
+    #[test]
+    fn exact_odds() {
+        let (hand, deck) = dealt("Ah Kh Qh Jh 2c");
+        let table = PayTable::jacks_or_better();
+        let draw = outcomes(&hand, &[4], deck.remaining(), &Rules::default(), &table).unwrap();
+
+        // 1 royal, 8 flushes, 3 straights and 12 high pairs out of 47
+        assert_eq!(47, draw.draws());
+        assert_eq!(1, draw.counts[HandCategory::RoyalFlush as usize]);
+        assert_eq!(8, draw.counts[HandCategory::Flush as usize]);
+        assert_eq!(3, draw.counts[HandCategory::Straight as usize]);
+        assert_eq!(12, draw.counts[HandCategory::Pair as usize]);
+        assert_eq!(250 + 8 * 6 + 3 * 4 + 12, draw.points);
+        assert!((draw.probability(HandCategory::Flush) - 8.0 / 47.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn ranked_choices() {
+        let (hand, deck) = dealt("Ah Kh Qh Jh 2c");
+        let table = PayTable::jacks_or_better();
+        let choices = rank_choices(&hand, deck.remaining(), &Rules::default(), &table).unwrap();
+
+        // 1 + 5 + 10 + 10 ways to change up to 3 cards
+        assert_eq!(26, choices.len());
+        assert_eq!(vec![4], choices[0].to_change);
+        assert!(choices.windows(2).all(|w| w[0].expected() >= w[1].expected()));
+    }
+
+    #[test]
+    fn invalid_choices() {
+        let (hand, deck) = dealt("Ah Kh Qh Jh 2c");
+        let rules = Rules::default();
+        let table = PayTable::standard();
+
+        assert_eq!(
+            Err(PokerError::TooManyChanges { requested: 4, max: 3 }),
+            outcomes(&hand, &[0, 1, 2, 3], deck.remaining(), &rules, &table)
+        );
+        assert_eq!(
+            Err(PokerError::InvalidIndex(5)),
+            outcomes(&hand, &[5], deck.remaining(), &rules, &table)
+        );
+        assert_eq!(
+            Err(PokerError::WrongHandSize(4)),
+            outcomes(&hand[..4], &[], deck.remaining(), &rules, &table)
+        );
+    }
+}
@@ -0,0 +1,263 @@
+use crate::game::error::PokerError;
+use crate::{Card, Rank, Suit};
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+// A deck owns every card of the game at all times. Each card is in
+// exactly one of three places: the draw pile, the discard pile or
+// dealt to the player. Cards only move between them through the
+// methods below, so no card can be duplicated or lost during a hand.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Deck {
+    draw: Vec<Card>,
+    discards: Vec<Card>,
+    dealt: Vec<Card>,
+}
+
+impl Deck {
+    // Full 52 card deck in order, cards are drawn from the end
+    pub fn new() -> Deck {
+        let draw = Suit::iter()
+            .flat_map(|suit| Rank::iter().map(move |rank| Card::new(rank, suit)))
+            .collect();
+
+        Deck {
+            draw,
+            discards: vec![],
+            dealt: vec![],
+        }
+    }
+
+    // Full deck with jokers added, up to 4
+    pub fn with_jokers(jokers: u8) -> Deck {
+        let mut deck = Deck::new();
+        deck.draw.extend((0..jokers.min(4)).map(Card::joker));
+
+        deck
+    }
+
+    // Deck with exactly these cards in the draw pile. Not checked, so
+    // only the crate builds decks out of cards it knows are whole.
+    pub(crate) fn from_cards(cards: Vec<Card>) -> Deck {
+        Deck {
+            draw: cards,
+            discards: vec![],
+            dealt: vec![],
+        }
+    }
+
+    // Deck in the middle of a hand, with these cards dealt and the
+    // others left to draw in this order. The caller checks the cards
+    // make up a whole deck.
+    pub(crate) fn with_dealt(draw: Vec<Card>, dealt: Vec<Card>) -> Deck {
+        Deck {
+            draw,
+            discards: vec![],
+            dealt,
+        }
+    }
+
+    pub fn shuffle<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        self.draw.shuffle(rng);
+    }
+
+    // Takes n cards from the top of the draw pile. Nothing is dealt
+    // if there aren't enough cards left.
+    pub fn deal(&mut self, n: usize) -> Result<Vec<Card>, PokerError> {
+        if n > self.draw.len() {
+            return Err(PokerError::NotEnoughCards {
+                requested: n,
+                remaining: self.draw.len(),
+            });
+        }
+
+        let cards = self.draw.split_off(self.draw.len() - n);
+        self.dealt.extend_from_slice(&cards);
+
+        Ok(cards)
+    }
+
+    // Sends the cards of the hand at the given indexes to the discard
+    // pile and replaces them with new ones from the draw pile.
+    // Returns the discarded cards. The hand is left untouched if any
+    // index is invalid, any card wasn't dealt from this deck or there
+    // aren't enough cards left.
+    pub fn replace(&mut self, hand: &mut [Card], indexes: &[usize]) -> Result<Vec<Card>, PokerError> {
+        for (n, i) in indexes.iter().enumerate() {
+            if *i >= hand.len() {
+                return Err(PokerError::InvalidIndex(*i));
+            }
+            if indexes[..n].contains(i) {
+                return Err(PokerError::DuplicateIndex(*i));
+            }
+            if !self.dealt.contains(&hand[*i]) {
+                return Err(PokerError::CardNotDealt(hand[*i]));
+            }
+        }
+
+        let new_cards = self.deal(indexes.len())?;
+        let mut discarded = vec![];
+
+        for (i, new_card) in indexes.iter().zip(new_cards) {
+            let old_card = hand[*i];
+            if let Some(pos) = self.dealt.iter().position(|c| *c == old_card) {
+                self.dealt.swap_remove(pos);
+            }
+            self.discards.push(old_card);
+            discarded.push(old_card);
+            hand[*i] = new_card;
+        }
+
+        Ok(discarded)
+    }
+
+    // Brings the dealt and discarded cards back to the draw pile
+    pub fn recollect(&mut self) {
+        self.draw.append(&mut self.discards);
+        self.draw.append(&mut self.dealt);
+    }
+
+    // Cards that can still be drawn
+    pub fn remaining(&self) -> &[Card] {
+        &self.draw
+    }
+
+    pub fn discards(&self) -> &[Card] {
+        &self.discards
+    }
+
+    pub fn dealt(&self) -> &[Card] {
+        &self.dealt
+    }
+
+    pub fn len(&self) -> usize {
+        self.draw.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.draw.is_empty()
+    }
+
+    // Number of cards owned by the deck wherever they are
+    pub fn total(&self) -> usize {
+        self.draw.len() + self.discards.len() + self.dealt.len()
+    }
+}
+
+impl Default for Deck {
+    fn default() -> Deck {
+        Deck::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::thread_rng;
+
+    fn all_cards(deck: &Deck) -> Vec<Card> {
+        let mut cards = [deck.remaining(), deck.discards(), deck.dealt()].concat();
+        cards.sort();
+        cards
+    }
+
+    #[test]
+    fn new_deck() {
+        let deck = Deck::new();
+        let mut cards = deck.remaining().to_vec();
+        cards.sort();
+        cards.dedup();
+
+        assert_eq!(52, cards.len());
+        assert_eq!(0, deck.discards().len());
+        assert_eq!(0, deck.dealt().len());
+    }
+
+    #[test]
+    fn jokers() {
+        let deck = Deck::with_jokers(2);
+
+        assert_eq!(54, deck.len());
+        assert_eq!(2, deck.remaining().iter().filter(|c| c.joker).count());
+        assert_ne!(deck.remaining()[52], deck.remaining()[53]);
+    }
+
+    #[test]
+    fn deal_cards() {
+        let mut deck = Deck::new();
+        deck.shuffle(&mut thread_rng());
+        let hand = deck.deal(5).unwrap();
+
+        assert_eq!(5, hand.len());
+        assert_eq!(47, deck.len());
+        assert_eq!(hand, deck.dealt());
+        assert!(hand.iter().all(|c| !deck.remaining().contains(c)));
+
+        // Asking for more cards than left deals nothing
+        assert_eq!(
+            Err(PokerError::NotEnoughCards { requested: 48, remaining: 47 }),
+            deck.deal(48)
+        );
+        assert_eq!(47, deck.len());
+    }
+
+    #[test]
+    fn replace_cards() {
+        let mut deck = Deck::new();
+        deck.shuffle(&mut thread_rng());
+        let mut hand = deck.deal(5).unwrap();
+        let hand_copy = hand.clone();
+        let discarded = deck.replace(&mut hand, &[0, 3]).unwrap();
+
+        assert_eq!(vec![hand_copy[0], hand_copy[3]], discarded);
+        assert_eq!(discarded, deck.discards());
+        assert_eq!(hand_copy[1..3], hand[1..3]);
+        assert_eq!(45, deck.len());
+        assert_eq!(5, deck.dealt().len());
+        assert_eq!(all_cards(&Deck::new()), all_cards(&deck));
+    }
+
+    #[test]
+    fn replace_invalid() {
+        let mut deck = Deck::new();
+        let mut hand = deck.deal(5).unwrap();
+        let hand_copy = hand.clone();
+
+        // Out of range and repeated indexes
+        assert_eq!(Err(PokerError::InvalidIndex(5)), deck.replace(&mut hand, &[5]));
+        assert_eq!(Err(PokerError::DuplicateIndex(1)), deck.replace(&mut hand, &[1, 1]));
+
+        // Cards that didn't come from this deck
+        let mut foreign = vec![deck.remaining()[0]];
+        assert_eq!(
+            Err(PokerError::CardNotDealt(foreign[0])),
+            deck.replace(&mut foreign, &[0])
+        );
+
+        // Not enough cards left
+        deck.deal(46).unwrap();
+        assert_eq!(
+            Err(PokerError::NotEnoughCards { requested: 2, remaining: 1 }),
+            deck.replace(&mut hand, &[0, 1])
+        );
+
+        assert_eq!(hand_copy, hand);
+        assert_eq!(52, deck.total());
+    }
+
+    #[test]
+    fn recollect_cards() {
+        let mut deck = Deck::new();
+        deck.shuffle(&mut thread_rng());
+        let mut hand = deck.deal(5).unwrap();
+        deck.replace(&mut hand, &[1, 2, 3]).unwrap();
+        deck.recollect();
+
+        // After recollecting, the deck contains the same
+        // cards it had when created
+        assert_eq!(52, deck.len());
+        assert_eq!(all_cards(&Deck::new()), all_cards(&deck));
+        assert_eq!(0, deck.discards().len());
+        assert_eq!(0, deck.dealt().len());
+    }
+}
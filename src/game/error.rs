@@ -0,0 +1,56 @@
+use crate::Card;
+use std::error::Error;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PokerError {
+    // The draw pile doesn't have enough cards left
+    NotEnoughCards { requested: usize, remaining: usize },
+    // A card index outside of the hand
+    InvalidIndex(usize),
+    // The same card was selected twice
+    DuplicateIndex(usize),
+    // A card in the hand that wasn't dealt from the deck
+    CardNotDealt(Card),
+    TooManyChanges { requested: usize, max: usize },
+    WrongHandSize(usize),
+    // Hands chosen out of more cards need between min and max cards
+    WrongCardCount { count: usize, min: usize, max: usize },
+    // Cards can only be chosen and drawn while a hand is in play
+    NoHandInPlay,
+    // A new hand can't be dealt before the one in play is drawn
+    HandInPlay,
+    // Cards given for a deck aren't the cards of the rules' deck
+    WrongDeck,
+}
+
+impl fmt::Display for PokerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PokerError::NotEnoughCards { requested, remaining } => write!(
+                f,
+                "Not enough cards in the deck: {} requested, {} left",
+                requested, remaining
+            ),
+            PokerError::InvalidIndex(i) => write!(f, "There is no card at position {}", i + 1),
+            PokerError::DuplicateIndex(i) => {
+                write!(f, "The card at position {} was selected twice", i + 1)
+            }
+            PokerError::CardNotDealt(card) => write!(f, "{} wasn't dealt from this deck", card),
+            PokerError::TooManyChanges { requested, max } => write!(
+                f,
+                "Only {} cards can be changed, {} were selected",
+                max, requested
+            ),
+            PokerError::WrongHandSize(n) => write!(f, "A hand needs 5 cards, got {}", n),
+            PokerError::WrongCardCount { count, min, max } => {
+                write!(f, "Expected {} to {} cards, got {}", min, max, count)
+            }
+            PokerError::NoHandInPlay => write!(f, "There is no hand in play"),
+            PokerError::HandInPlay => write!(f, "The hand in play has to be drawn first"),
+            PokerError::WrongDeck => write!(f, "The cards don't make up the deck of the rules"),
+        }
+    }
+}
+
+impl Error for PokerError {}
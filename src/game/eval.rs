@@ -0,0 +1,601 @@
+use crate::game::hand::{BestHand, HandCategory, HandValue};
+use crate::{Card, Rank, Suit};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+// Table based 5 card evaluator.
+//
+// Every 5 card hand falls in one of three cases:
+// - five different ranks of the same suit, looked up in `flushes` by
+//   the bit mask of its ranks
+// - five different ranks of mixed suits, looked up in `unique` by the
+//   same bit mask
+// - some repeated rank, looked up in `paired` by the product of one
+//   prime per rank, which is the same for any order of the cards
+//
+// The tables are filled once, the first time a hand is evaluated, by
+// running the direct evaluator on one hand of each kind.
+//
+// With more than one deck a hand can repeat a rank and still have
+// every card of one suit. Those hands are a flush unless their
+// repeated ranks make something better.
+
+// One prime per rank, from the two to the ace
+const PRIMES: [u32; 13] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41];
+
+struct Tables {
+    flushes: Vec<HandValue>,
+    unique: Vec<HandValue>,
+    // Sorted by prime product for binary search
+    paired: Vec<(u32, HandValue)>,
+}
+
+static TABLES: OnceLock<Tables> = OnceLock::new();
+
+fn tables() -> &'static Tables {
+    TABLES.get_or_init(build_tables)
+}
+
+fn build_tables() -> Tables {
+    let placeholder = HandValue::new(HandCategory::HighCard, &[]);
+    let mut flushes = vec![placeholder; 1 << 13];
+    let mut unique = vec![placeholder; 1 << 13];
+    let mut paired = vec![];
+
+    // Every multiset of 5 ranks, five of a kind included
+    let mut counts = [0u8; 13];
+    for_each_rank_multiset(&mut counts, 0, 5, &mut |counts| {
+        let mut cards = vec![];
+        for (i, count) in counts.iter().enumerate() {
+            for n in 0..*count {
+                cards.push(Card::new(Rank::ALL[i], Suit::ALL[n as usize % 4]));
+            }
+        }
+
+        // Repeated ranks get different suits, different ranks all
+        // start with spades so they make a flush
+        if counts.iter().all(|c| *c <= 1) {
+            let bits = rank_bits(&cards);
+            unique[bits] = evaluate_direct(&alternate_suits(&cards));
+            flushes[bits] = evaluate_direct(&cards);
+        } else {
+            paired.push((prime_product(&cards), evaluate_direct(&cards)));
+        }
+    });
+
+    paired.sort_unstable_by_key(|entry| entry.0);
+
+    Tables {
+        flushes,
+        unique,
+        paired,
+    }
+}
+
+// Calls f with every way of spreading `left` cards over the ranks from
+// `from` on, counted by rank index
+fn for_each_rank_multiset<F: FnMut(&[u8])>(counts: &mut [u8], from: usize, left: u8, f: &mut F) {
+    if left == 0 {
+        f(counts);
+        return;
+    }
+
+    for i in from..counts.len() {
+        counts[i] += 1;
+        for_each_rank_multiset(counts, i, left - 1, f);
+        counts[i] -= 1;
+    }
+}
+
+// Same ranks with the first card on a different suit so they can't
+// make a flush
+fn alternate_suits(cards: &[Card]) -> Vec<Card> {
+    cards
+        .iter()
+        .enumerate()
+        .map(|(i, c)| Card::new(c.rank, if i == 0 { Suit::Hearts } else { Suit::Spades }))
+        .collect()
+}
+
+fn rank_index(card: &Card) -> usize {
+    card.rank as usize - Rank::Two as usize
+}
+
+fn rank_bits(cards: &[Card]) -> usize {
+    cards.iter().fold(0, |bits, c| bits | 1 << rank_index(c))
+}
+
+fn prime_product(cards: &[Card]) -> u32 {
+    cards.iter().map(|c| PRIMES[rank_index(c)]).product()
+}
+
+pub fn evaluate(cards: &[Card; 5]) -> HandValue {
+    let tables = tables();
+    let bits = rank_bits(cards);
+
+    if bits.count_ones() == 5 {
+        let suit = cards[0].suit;
+        if cards.iter().all(|c| c.suit == suit) {
+            return tables.flushes[bits];
+        }
+
+        return tables.unique[bits];
+    }
+
+    let product = prime_product(cards);
+    let value = match tables.paired.binary_search_by_key(&product, |entry| entry.0) {
+        Ok(i) => tables.paired[i].1,
+        // Every multiset of ranks is in the table
+        Err(_) => evaluate_direct(cards),
+    };
+
+    match is_suited(cards) {
+        true => value.max(suited_value(cards)),
+        false => value,
+    }
+}
+
+fn is_suited(cards: &[Card]) -> bool {
+    cards.iter().all(|c| c.suit == cards[0].suit)
+}
+
+// Flush of cards that may repeat ranks
+fn suited_value(cards: &[Card]) -> HandValue {
+    let mut ranks: Vec<u8> = cards.iter().map(|c| c.rank.value()).collect();
+    ranks.sort_unstable_by(|a, b| b.cmp(a));
+
+    HandValue::new(HandCategory::Flush, &ranks)
+}
+
+// Evaluates a hand where the cards for which `is_wild` is true can
+// stand for any card of the given ranks. Every wild card takes the
+// suit of the natural cards when they all share one, as that never
+// makes the hand worse, and every combination of ranks is tried for
+// them with the `evaluate` function. A royal flush that uses wild
+// cards is a wild royal flush.
+pub fn evaluate_wild<F, E>(cards: &[Card; 5], is_wild: F, ranks: &[Rank], evaluate: E) -> HandValue
+where
+    F: Fn(&Card) -> bool,
+    E: Fn(&[Card; 5]) -> HandValue,
+{
+    let naturals: Vec<Card> = cards.iter().filter(|c| !is_wild(c)).copied().collect();
+    let wilds = 5 - naturals.len();
+
+    if wilds == 0 {
+        return evaluate(cards);
+    }
+
+    // Taking the suit of the first natural card is enough, it can
+    // only make a flush when all of them share it
+    let suit = naturals.first().map_or(Suit::Spades, |c| c.suit);
+
+    let mut hand = [Card::new(Rank::Ace, suit); 5];
+    hand[..naturals.len()].copy_from_slice(&naturals);
+
+    let mut best = evaluate(&hand);
+    let mut counts = vec![0u8; ranks.len()];
+    for_each_rank_multiset(&mut counts, 0, wilds as u8, &mut |counts| {
+        let mut slot = naturals.len();
+        for (i, count) in counts.iter().enumerate() {
+            for _ in 0..*count {
+                hand[slot] = Card::new(ranks[i], suit);
+                slot += 1;
+            }
+        }
+
+        best = best.max(evaluate(&hand));
+    });
+
+    if best.category() == HandCategory::RoyalFlush {
+        return HandValue::new(HandCategory::WildRoyalFlush, &[14, 13, 12, 11, 10]);
+    }
+
+    best
+}
+
+// Evaluates a hand of the short deck, which has no cards from the two
+// to the five. The ace also plays low in A-6-7-8-9, the lowest
+// straight, and a flush beats a full house as it's the rarer hand.
+pub fn evaluate_short_deck(cards: &[Card; 5]) -> HandValue {
+    let mut value = evaluate(cards);
+    let short_wheel = [Rank::Ace, Rank::Six, Rank::Seven, Rank::Eight, Rank::Nine];
+
+    if rank_bits(cards) == rank_bits(&short_wheel.map(|r| Card::new(r, Suit::Spades))) {
+        let category = match value.category() {
+            HandCategory::Flush => HandCategory::StraightFlush,
+            _ => HandCategory::Straight,
+        };
+        value = HandValue::new(category, &[9, 8, 7, 6, 1]);
+    }
+
+    // A full house of one suit, only possible with more than one
+    // deck, is worth more as a flush
+    if value.category() == HandCategory::FullHouse && is_suited(cards) {
+        value = suited_value(cards);
+    }
+
+    match value.category() {
+        HandCategory::Flush => value.ranked_as(HandCategory::FullHouse),
+        HandCategory::FullHouse => value.ranked_as(HandCategory::Flush),
+        _ => value,
+    }
+}
+
+// Evaluates a hand by counting its ranks and suits. Slower than the
+// tables, which are built from it.
+fn evaluate_direct(hand: &[Card]) -> HandValue {
+    let mut suits = HashMap::new();
+    let mut ranks = HashMap::new();
+
+    for card in hand {
+        let suit_counter = suits.entry(card.suit).or_insert(0);
+        let rank_counter = ranks.entry(card.rank).or_insert(0);
+
+        *suit_counter += 1;
+        *rank_counter += 1;
+    }
+
+    // Ranks grouped by how many times they repeat, bigger groups
+    // first and higher ranks first among groups of the same size
+    let mut groups: Vec<(u8, u8)> = ranks
+        .into_iter()
+        .map(|(rank, count)| (count, rank.value()))
+        .collect();
+    groups.sort_unstable_by(|a, b| b.cmp(a));
+
+    // rank_keys are the ranks in the order they break ties
+    // ranks_count is how many times each of them repeated
+    let mut rank_keys: Vec<u8> = groups.iter().map(|g| g.1).collect();
+    let ranks_count: Vec<u8> = groups.iter().map(|g| g.0).collect();
+
+    // Flush
+    let flush_found = suits.len() == 1;
+
+    // Straight
+    let mut straight_found = false;
+    if rank_keys.len() == 5 {
+        if let Some(high) = straight_high(&rank_keys) {
+            straight_found = true;
+
+            // The ace of a wheel counts as its low value
+            if high == 5 {
+                rank_keys = vec![5, 4, 3, 2, 1];
+            }
+        }
+    }
+
+    let category = match (flush_found, straight_found, &ranks_count[..]) {
+        (_, _, [5]) => HandCategory::FiveOfAKind,
+        (true, true, _) if rank_keys[0] == Rank::Ace.value() => HandCategory::RoyalFlush,
+        (true, true, _) => HandCategory::StraightFlush,
+        (_, _, [4, ..]) => HandCategory::FourOfAKind,
+        (_, _, [3, 2]) => HandCategory::FullHouse,
+        (true, false, _) => HandCategory::Flush,
+        (false, true, _) => HandCategory::Straight,
+        (_, _, [3, ..]) => HandCategory::ThreeOfAKind,
+        (_, _, [2, 2, ..]) => HandCategory::TwoPair,
+        (_, _, [2, ..]) => HandCategory::Pair,
+        _ => HandCategory::HighCard,
+    };
+
+    HandValue::new(category, &rank_keys)
+}
+
+// Highest card of the best straight that can be made with the given
+// rank values, in any order and with repeats. The wheel's high card
+// is the five.
+fn straight_high(ranks: &[u8]) -> Option<u8> {
+    // One bit per rank, the ace also sets the bit below the two
+    let mut bits = 0u16;
+    for rank in ranks {
+        bits |= 1 << rank;
+        if *rank == Rank::Ace.value() {
+            bits |= 1 << 1;
+        }
+    }
+
+    (5..=Rank::Ace.value()).rev().find(|high| (bits >> (high - 4)) & 0b11111 == 0b11111)
+}
+
+// Best 5 card hand out of 5 to 7 cards, along with the cards that
+// make it
+pub fn best_five(cards: &[Card]) -> Option<BestHand> {
+    if !(5..=7).contains(&cards.len()) {
+        return None;
+    }
+
+    let mut best: Option<BestHand> = None;
+
+    for five in five_card_subsets(cards) {
+        let value = evaluate(&five);
+        if best.map_or(true, |b| value > b.value) {
+            best = Some(BestHand { value, cards: five });
+        }
+    }
+
+    best
+}
+
+// Every subset of 5 cards, as a bit mask over up to 32 cards
+pub(crate) fn five_card_subsets(cards: &[Card]) -> impl Iterator<Item = [Card; 5]> + '_ {
+    (0u32..(1 << cards.len()))
+        .filter(|mask| mask.count_ones() == 5)
+        .map(move |mask| {
+            let mut five = [cards[0]; 5];
+            let chosen = cards.iter().enumerate().filter(|(i, _)| mask & (1 << i) != 0);
+            for (slot, (_, card)) in five.iter_mut().zip(chosen) {
+                *slot = *card;
+            }
+
+            five
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::deck::Deck;
+    use crate::parse_hand;
+    use rand::seq::SliceRandom;
+    use rand::thread_rng;
+    use std::collections::HashSet;
+    use std::time::{Duration, Instant};
+
+    fn hand(s: &str) -> [Card; 5] {
+        parse_hand(s).unwrap().try_into().unwrap()
+    }
+
+    // Calls f with every 5 card hand of the deck
+    fn for_each_hand<F: FnMut(&[Card; 5])>(deck: &[Card], mut f: F) {
+        let n = deck.len();
+        for a in 0..n {
+            for b in a + 1..n {
+                for c in b + 1..n {
+                    for d in c + 1..n {
+                        for e in d + 1..n {
+                            f(&[deck[a], deck[b], deck[c], deck[d], deck[e]]);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn table_sizes() {
+        let tables = tables();
+
+        // 1287 sets of 5 different ranks, 4901 multisets with repeats
+        assert_eq!(4901, tables.paired.len());
+        assert_eq!(1287, tables.unique.iter().filter(|v| v.ranks().len() == 5).count());
+        assert_eq!(1287, tables.flushes.iter().filter(|v| v.ranks().len() == 5).count());
+    }
+
+    #[test]
+    fn category_counts() {
+        let deck = Deck::new();
+        let mut counts: HashMap<HandCategory, u32> = HashMap::new();
+        let mut values = HashSet::new();
+
+        for_each_hand(deck.remaining(), |cards| {
+            let value = evaluate(cards);
+            *counts.entry(value.category()).or_insert(0) += 1;
+            values.insert(value);
+        });
+
+        let expected = [
+            (HandCategory::HighCard, 1_302_540),
+            (HandCategory::Pair, 1_098_240),
+            (HandCategory::TwoPair, 123_552),
+            (HandCategory::ThreeOfAKind, 54_912),
+            (HandCategory::Straight, 10_200),
+            (HandCategory::Flush, 5_108),
+            (HandCategory::FullHouse, 3_744),
+            (HandCategory::FourOfAKind, 624),
+            (HandCategory::StraightFlush, 36),
+            (HandCategory::RoyalFlush, 4),
+        ];
+
+        for (category, count) in expected {
+            assert_eq!(Some(&count), counts.get(&category), "{}", category);
+        }
+        assert_eq!(2_598_960, counts.values().sum::<u32>());
+
+        // Poker has 7462 distinct 5 card hand values
+        assert_eq!(7462, values.len());
+    }
+
+    #[test]
+    fn matches_direct_evaluator() {
+        let mut cards = Deck::new().remaining().to_vec();
+        let mut rng = thread_rng();
+
+        for _ in 0..20_000 {
+            cards.shuffle(&mut rng);
+            let hand: [Card; 5] = cards[..5].try_into().unwrap();
+
+            assert_eq!(evaluate_direct(&hand), evaluate(&hand), "{:?}", hand);
+        }
+    }
+
+    // Every hand of the deck, too slow for every run. Run with
+    // `cargo test --release -- --ignored`
+    #[test]
+    #[ignore]
+    fn matches_direct_evaluator_everywhere() {
+        for_each_hand(Deck::new().remaining(), |hand| {
+            assert_eq!(evaluate_direct(hand), evaluate(hand), "{:?}", hand);
+        });
+    }
+
+    // The tables have to beat the evaluator they're built from, and get
+    // through every hand well under a second in release
+    #[test]
+    #[ignore]
+    fn speed() {
+        let deck = Deck::new();
+        tables();
+
+        let start = Instant::now();
+        let mut tables_sum = 0;
+        for_each_hand(deck.remaining(), |hand| tables_sum += evaluate(hand).category() as u32);
+        let tables_time = start.elapsed();
+
+        let start = Instant::now();
+        let mut direct_sum = 0;
+        for_each_hand(deck.remaining(), |hand| direct_sum += evaluate_direct(hand).category() as u32);
+        let direct_time = start.elapsed();
+
+        assert_eq!(direct_sum, tables_sum);
+        assert!(tables_time < direct_time);
+        if !cfg!(debug_assertions) {
+            assert!(tables_time < Duration::from_millis(500), "{:?}", tables_time);
+        }
+    }
+
+    #[test]
+    fn straights() {
+        assert_eq!(Some(14), straight_high(&[10, 11, 12, 13, 14]));
+        assert_eq!(Some(5), straight_high(&[14, 2, 3, 4, 5]));
+        assert_eq!(Some(6), straight_high(&[14, 2, 3, 4, 5, 6]));
+        // Unsorted with repeats
+        assert_eq!(Some(9), straight_high(&[9, 5, 7, 7, 6, 8, 5]));
+        assert_eq!(None, straight_high(&[13, 14, 2, 3, 4]));
+        assert_eq!(None, straight_high(&[2, 3, 4, 5]));
+    }
+
+    #[test]
+    fn best_of_seven() {
+        let cards = parse_hand("2c 9h Th Jh Qh Kh 2d").unwrap();
+        let best = best_five(&cards).unwrap();
+        let mut used = best.cards.to_vec();
+        used.sort();
+
+        assert_eq!(HandCategory::StraightFlush, best.value.category());
+        assert_eq!(parse_hand("9h Th Jh Qh Kh").unwrap(), used);
+
+        // The full house beats the flush and the straight in the same cards
+        let cards = parse_hand("7s 7h 7d 8s 8c 9s Ts").unwrap();
+        let best = best_five(&cards).unwrap();
+        assert_eq!(HandCategory::FullHouse, best.value.category());
+        assert!(!best.cards.contains(&"9s".parse().unwrap()));
+
+        // The best kicker is kept
+        let cards = parse_hand("As Ah 3c 4d 9s Kh 2c").unwrap();
+        let best = best_five(&cards).unwrap();
+        assert_eq!(
+            vec![Rank::Ace, Rank::King, Rank::Nine, Rank::Four],
+            best.value.ranks()
+        );
+    }
+
+    #[test]
+    fn best_of_six_and_five() {
+        let cards = parse_hand("As 2h 3c 4d 5s 6h").unwrap();
+        assert_eq!(Rank::Six, best_five(&cards).unwrap().value.high_rank());
+
+        let cards = parse_hand("As 2h 3c 4d 5s").unwrap();
+        assert_eq!(evaluate(&hand("As 2h 3c 4d 5s")), best_five(&cards).unwrap().value);
+    }
+
+    #[test]
+    fn best_wrong_count() {
+        assert_eq!(None, best_five(&parse_hand("As 2h 3c 4d").unwrap()));
+        assert_eq!(None, best_five(&parse_hand("As 2h 3c 4d 5s 6h 7c 8d").unwrap()));
+    }
+
+    fn wild(s: &str, rank: Rank) -> HandValue {
+        evaluate_wild(&hand(s), |c| c.joker || c.rank == rank, &Rank::ALL, evaluate)
+    }
+
+    #[test]
+    fn wild_cards() {
+        let deuces = Rank::Two;
+
+        // Wild cards complete the best hand they can
+        assert_eq!(HandCategory::ThreeOfAKind, wild("9s 9h 2d Kc 3c", deuces).category());
+        assert_eq!(HandCategory::FiveOfAKind, wild("9s 9h 2d 2c 9c", deuces).category());
+        assert_eq!(HandCategory::StraightFlush, wild("5h 6h 2d 8h 9h", deuces).category());
+        assert_eq!(HandCategory::Flush, wild("5h Jh 2d 8h 3h", deuces).category());
+        assert_eq!(HandCategory::Straight, wild("5h 6c 2d 8h 9h", deuces).category());
+        assert_eq!(HandCategory::Pair, wild("Ks 9h 2d Jc 3c", deuces).category());
+        assert_eq!(HandCategory::FourOfAKind, wild("9s 9h 2d 2c 3c", deuces).category());
+
+        // The best five of a kind
+        assert_eq!(Rank::Ace, wild("2s 2h 2d 2c Ac", deuces).high_rank());
+        assert!(wild("Ks Kh 2d 2c Kc", deuces) > wild("Qs Qh 2d 2c Qc", deuces));
+
+        // Jokers are always wild
+        assert_eq!(HandCategory::Pair, wild("Jk 9h 4d Kc 3c", deuces).category());
+        assert_eq!(Rank::King, wild("Jk 9h 4d Kc 3c", deuces).high_rank());
+        assert_eq!(HandCategory::FiveOfAKind, wild("Jk 5h 2d 2c 2h", deuces).category());
+
+        // No wild cards is a natural hand
+        assert_eq!(evaluate(&hand("9s 9h 4d Kc 3c")), wild("9s 9h 4d Kc 3c", deuces));
+    }
+
+    #[test]
+    fn wild_royal_flush() {
+        assert_eq!(HandCategory::RoyalFlush, wild("Ah Kh Qh Jh Th", Rank::Two).category());
+        assert_eq!(HandCategory::WildRoyalFlush, wild("Ah Kh Qh Jh 2c", Rank::Two).category());
+        assert_eq!(HandCategory::WildRoyalFlush, wild("Jk Kh Qh Jh Th", Rank::Two).category());
+        assert_eq!(HandCategory::WildRoyalFlush, wild("2s 2h 2d 2c Jk", Rank::Two).category());
+        assert!(wild("Ah Kh Qh Jh Th", Rank::Two) > wild("Ah Kh Qh Jh 2c", Rank::Two));
+        assert!(wild("Ah Kh Qh Jh 2c", Rank::Two) > wild("As Ah Ad 2h 2c", Rank::Two));
+    }
+
+    #[test]
+    fn short_deck() {
+        let value = |s| evaluate_short_deck(&hand(s));
+
+        // A-6-7-8-9 is the lowest straight
+        assert_eq!(HandCategory::Straight, value("As 6h 7d 8c 9s").category());
+        assert_eq!(HandCategory::StraightFlush, value("As 6s 7s 8s 9s").category());
+        assert!(value("6s 7h 8d 9c Ts") > value("As 6h 7d 8c 9s"));
+        assert!(value("As 6h 7d 8c 9s") > value("As Ah Ad Kc Qs"));
+
+        // Flushes beat full houses but not four of a kind
+        assert!(value("6s 8s 9s Js Ks") > value("As Ah Ad Kc Ks"));
+        assert!(value("6s 6h 6d 6c Ks") > value("Ts Js Qs Ks 8s"));
+        assert_eq!(HandCategory::Flush, value("6s 8s 9s Js Ks").category());
+        assert_eq!(HandCategory::RoyalFlush, value("Ts Js Qs Ks As").category());
+    }
+
+    #[test]
+    fn short_deck_wilds() {
+        let short_ranks = &Rank::ALL[4..];
+        let value = |s| evaluate_wild(&hand(s), |c: &Card| c.joker, short_ranks, evaluate_short_deck);
+
+        // The joker fills the short straight and never becomes a five
+        assert_eq!(HandCategory::Straight, value("Jk 6h 7d 8c 9s").category());
+        assert_eq!(Rank::Ten, value("Jk 6h 7d 8c 9s").high_rank());
+        assert_eq!(HandCategory::Straight, value("Jk Ah 7d 8c 9s").category());
+        assert_eq!(HandCategory::Flush, value("Jk 6s 8s Ts Qs").category());
+    }
+
+    #[test]
+    fn repeated_cards() {
+        // Cards from a two deck shoe
+        let value = |s| evaluate(&hand(s));
+
+        assert_eq!(HandCategory::Flush, value("As As Ks Qs 9s").category());
+        assert_eq!(HandCategory::Flush, value("As As Ks Ks 9s").category());
+        assert!(value("As As Ks Qs 9s") > value("As Ks Qs Js 9s"));
+        assert_eq!(HandCategory::FullHouse, value("As As Ks Ks Ks").category());
+        assert_eq!(HandCategory::FourOfAKind, value("As As Ah Ad 9s").category());
+        assert_eq!(HandCategory::FiveOfAKind, value("As As Ah Ad Ac").category());
+
+        // In the short deck the flush is the better hand
+        let short = evaluate_short_deck(&hand("As As Ks Ks Ks"));
+        assert_eq!(HandCategory::Flush, short.category());
+        assert!(short > evaluate_short_deck(&hand("Ah Ad Kc Kh Ks")));
+    }
+
+    #[test]
+    fn lookups() {
+        assert_eq!(HandCategory::RoyalFlush, evaluate(&hand("Ah Kh Qh Jh Th")).category());
+        assert_eq!(HandCategory::Straight, evaluate(&hand("5c 4h 3d 2s Ah")).category());
+        assert_eq!(HandCategory::FullHouse, evaluate(&hand("5c 5h 3d 3s 3h")).category());
+        assert!(evaluate(&hand("6c 5h 4d 3s 2h")) > evaluate(&hand("5c 4h 3d 2s Ah")));
+    }
+}
@@ -0,0 +1,187 @@
+use crate::{Card, Rank};
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum HandCategory {
+    HighCard,
+    Pair,
+    TwoPair,
+    ThreeOfAKind,
+    Straight,
+    Flush,
+    FullHouse,
+    FourOfAKind,
+    StraightFlush,
+    // Only possible with wild cards
+    FiveOfAKind,
+    // A royal flush that needed wild cards
+    WildRoyalFlush,
+    RoyalFlush,
+}
+
+impl HandCategory {
+    pub const ALL: [HandCategory; 12] = [
+        HandCategory::HighCard,
+        HandCategory::Pair,
+        HandCategory::TwoPair,
+        HandCategory::ThreeOfAKind,
+        HandCategory::Straight,
+        HandCategory::Flush,
+        HandCategory::FullHouse,
+        HandCategory::FourOfAKind,
+        HandCategory::StraightFlush,
+        HandCategory::FiveOfAKind,
+        HandCategory::WildRoyalFlush,
+        HandCategory::RoyalFlush,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            HandCategory::HighCard => "High Card",
+            HandCategory::Pair => "Pair",
+            HandCategory::TwoPair => "Two Pair",
+            HandCategory::ThreeOfAKind => "Three of a Kind",
+            HandCategory::Straight => "Straight",
+            HandCategory::Flush => "Flush",
+            HandCategory::FullHouse => "Full House",
+            HandCategory::FourOfAKind => "Four of a Kind",
+            HandCategory::StraightFlush => "Straight Flush",
+            HandCategory::FiveOfAKind => "Five of a Kind",
+            HandCategory::WildRoyalFlush => "Wild Royal Flush",
+            HandCategory::RoyalFlush => "Royal Flush",
+        }
+    }
+}
+
+impl fmt::Display for HandCategory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+// Strength of a 5 card hand. Two values compare the same way the hands
+// they come from do: first by category, then by the ranks that decide
+// ties within it (the pair before the kickers, the three of a kind
+// before the pair of a full house and so on). Suits never break ties.
+//
+// The value is packed in a u32: the category takes bits 20 to 23 and
+// each of the five significant ranks takes 4 bits below it, most
+// significant first. Unused rank slots are 0, the ace of a wheel
+// straight is 1. Bits 24 to 27 hold the tier of the category, which
+// is the category itself unless the rules rank it somewhere else.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HandValue(u32);
+
+impl HandValue {
+    // Ranks are given as numeric values, most significant first
+    pub(crate) fn new(category: HandCategory, ranks: &[u8]) -> HandValue {
+        let mut value = (category as u32) << 24 | (category as u32) << 20;
+
+        for (i, rank) in ranks.iter().take(5).enumerate() {
+            value |= (*rank as u32) << (16 - 4 * i);
+        }
+
+        HandValue(value)
+    }
+
+    pub fn category(&self) -> HandCategory {
+        HandCategory::ALL[((self.0 >> 20) & 0xF) as usize]
+    }
+
+    // Same hand ranked in the tier of another category
+    pub(crate) fn ranked_as(&self, tier: HandCategory) -> HandValue {
+        HandValue(self.0 & 0xFF_FFFF | (tier as u32) << 24)
+    }
+
+    // Most significant rank, the one of the biggest group or the
+    // highest card. The wheel straight reports its five.
+    pub fn high_rank(&self) -> Rank {
+        rank_from_nibble(self.top_value())
+    }
+
+    // Value of the most significant rank, 1 for a low ace
+    pub(crate) fn top_value(&self) -> u8 {
+        ((self.0 >> 16) & 0xF) as u8
+    }
+
+    // Ranks that decide ties between hands of the same category, most
+    // significant first. A wheel straight reports its ace last.
+    pub fn ranks(&self) -> Vec<Rank> {
+        (0..5)
+            .map(|i| ((self.0 >> (16 - 4 * i)) & 0xF) as u8)
+            .filter(|r| *r != 0)
+            .map(rank_from_nibble)
+            .collect()
+    }
+}
+
+// Low aces are stored as 1
+fn rank_from_nibble(value: u8) -> Rank {
+    match value {
+        1 => Rank::Ace,
+        v => Rank::from_value(v).unwrap_or(Rank::Two),
+    }
+}
+
+// A hand value along with the 5 cards that make it, when they are
+// chosen out of more cards
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BestHand {
+    pub value: HandValue,
+    pub cards: [Card; 5],
+}
+
+impl fmt::Display for HandValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let ranks: Vec<String> = self.ranks().iter().map(|r| r.letter().to_string()).collect();
+        write!(f, "{} ({})", self.category(), ranks.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn category_round_trip() {
+        for category in HandCategory::ALL {
+            assert_eq!(category, HandValue::new(category, &[14, 13]).category());
+        }
+    }
+
+    #[test]
+    fn ranks() {
+        let value = HandValue::new(HandCategory::Pair, &[12, 14, 9, 3]);
+        assert_eq!(vec![Rank::Queen, Rank::Ace, Rank::Nine, Rank::Three], value.ranks());
+        assert_eq!(Rank::Queen, value.high_rank());
+
+        let wheel = HandValue::new(HandCategory::Straight, &[5, 4, 3, 2, 1]);
+        assert_eq!(Rank::Ace, wheel.ranks()[4]);
+        assert_eq!(Rank::Five, wheel.high_rank());
+        assert_eq!("Straight (5 4 3 2 A)", wheel.to_string());
+    }
+
+    #[test]
+    fn tiers() {
+        let flush = HandValue::new(HandCategory::Flush, &[9, 8, 7, 6, 5]);
+        let full_house = HandValue::new(HandCategory::FullHouse, &[14, 13]);
+        assert!(full_house > flush);
+
+        let flush = flush.ranked_as(HandCategory::FullHouse);
+        let full_house = full_house.ranked_as(HandCategory::Flush);
+        assert!(flush > full_house);
+        assert_eq!(HandCategory::Flush, flush.category());
+        assert_eq!(vec![Rank::Ace, Rank::King], full_house.ranks());
+    }
+
+    #[test]
+    fn ordering() {
+        let pair = HandValue::new(HandCategory::Pair, &[2, 5, 4, 3]);
+        let two_pair = HandValue::new(HandCategory::TwoPair, &[3, 2, 4]);
+        assert!(two_pair > pair);
+
+        let kicker_low = HandValue::new(HandCategory::Pair, &[10, 14, 9, 3]);
+        let kicker_high = HandValue::new(HandCategory::Pair, &[10, 14, 9, 4]);
+        assert!(kicker_high > kicker_low);
+    }
+}
@@ -0,0 +1,184 @@
+use crate::game::error::PokerError;
+use crate::game::eval;
+use crate::game::hand::BestHand;
+use crate::game::lowball::{self, LowValue};
+use crate::{Card, Rank};
+
+// Split pot games award half of the pot to the best high hand and half
+// to the best ace to five low hand, which only counts when it's
+// 8 or better. Every low hand is taken from the same cards as the high
+// hand, but both halves may use different cards.
+
+// Highest card a low hand may have
+pub const LOW_QUALIFIER: Rank = Rank::Eight;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BestLow {
+    pub value: LowValue,
+    pub cards: [Card; 5],
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HiLo {
+    pub high: BestHand,
+    // None when no low hand qualifies
+    pub low: Option<BestLow>,
+}
+
+// Best high and low hands using any 5 of 5 to 7 cards, as in stud
+pub fn hi_lo(cards: &[Card]) -> Result<HiLo, PokerError> {
+    if !(5..=7).contains(&cards.len()) {
+        return Err(PokerError::WrongCardCount {
+            count: cards.len(),
+            min: 5,
+            max: 7,
+        });
+    }
+
+    Ok(split(eval::five_card_subsets(cards)))
+}
+
+// Best high and low hands using exactly 2 of the hole cards and 3 of
+// the board cards, as in Omaha
+pub fn omaha_hi_lo(hole: &[Card], board: &[Card]) -> Result<HiLo, PokerError> {
+    if !(2..=6).contains(&hole.len()) {
+        return Err(PokerError::WrongCardCount {
+            count: hole.len(),
+            min: 2,
+            max: 6,
+        });
+    }
+    if !(3..=5).contains(&board.len()) {
+        return Err(PokerError::WrongCardCount {
+            count: board.len(),
+            min: 3,
+            max: 5,
+        });
+    }
+
+    let mut hands = vec![];
+    for (i, first) in hole.iter().enumerate() {
+        for second in &hole[i + 1..] {
+            for (j, a) in board.iter().enumerate() {
+                for (k, b) in board.iter().enumerate().skip(j + 1) {
+                    for c in &board[k + 1..] {
+                        hands.push([*first, *second, *a, *b, *c]);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(split(hands.into_iter()))
+}
+
+// Picks the best high hand and the best qualifying low hand out of
+// every given 5 card hand
+fn split<I: Iterator<Item = [Card; 5]>>(hands: I) -> HiLo {
+    let mut high: Option<BestHand> = None;
+    let mut low: Option<BestLow> = None;
+
+    for cards in hands {
+        let value = eval::evaluate(&cards);
+        if high.map_or(true, |h| value > h.value) {
+            high = Some(BestHand { value, cards });
+        }
+
+        let low_value = lowball::ace_to_five(&cards);
+        if low_value.qualifies(LOW_QUALIFIER) && low.map_or(true, |l| low_value > l.value) {
+            low = Some(BestLow {
+                value: low_value,
+                cards,
+            });
+        }
+    }
+
+    HiLo {
+        // Callers always give at least one hand
+        high: high.expect("at least one hand to split"),
+        low,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::hand::HandCategory;
+    use crate::parse_hand;
+
+    fn cards(s: &str) -> Vec<Card> {
+        parse_hand(s).unwrap()
+    }
+
+    fn sorted(cards: &[Card]) -> Vec<Card> {
+        let mut cards = cards.to_vec();
+        cards.sort();
+        cards
+    }
+
+    #[test]
+    fn stud_split() {
+        let split = hi_lo(&cards("As 2d 3h 4c 5s Ks Kd")).unwrap();
+
+        // The wheel is both a straight and the best low
+        assert_eq!(HandCategory::Straight, split.high.value.category());
+        let low = split.low.unwrap();
+        assert_eq!("5-4-3-2-A", low.value.to_string());
+        assert_eq!(sorted(&cards("As 2d 3h 4c 5s")), sorted(&low.cards));
+    }
+
+    #[test]
+    fn different_cards_per_half() {
+        let split = hi_lo(&cards("Kh Qh 2h 3c 7d 6h 4h")).unwrap();
+
+        assert_eq!(HandCategory::Flush, split.high.value.category());
+        assert_eq!(sorted(&cards("Kh Qh 6h 4h 2h")), sorted(&split.high.cards));
+
+        let low = split.low.unwrap();
+        assert_eq!("7-6-4-3-2", low.value.to_string());
+        assert_eq!(sorted(&cards("7d 6h 4h 3c 2h")), sorted(&low.cards));
+    }
+
+    #[test]
+    fn no_qualifying_low() {
+        let split = hi_lo(&cards("Kh Qh 9h 2c 3c 9d 6h")).unwrap();
+
+        assert_eq!(HandCategory::Pair, split.high.value.category());
+        assert_eq!(None, split.low);
+    }
+
+    #[test]
+    fn omaha_two_hole_cards() {
+        // A flush on the board doesn't count without two hearts in hand
+        let hole = cards("As Kd 2c 3c");
+        let board = cards("4h 5h 8h Jh Qh");
+        let split = omaha_hi_lo(&hole, &board).unwrap();
+
+        assert_eq!(HandCategory::HighCard, split.high.value.category());
+        assert_eq!(Rank::Ace, split.high.value.high_rank());
+
+        // The low takes A-2 from the hand and 4-5-8 from the board
+        let low = split.low.unwrap();
+        assert_eq!(sorted(&cards("As 2c 4h 5h 8h")), sorted(&low.cards));
+        assert_eq!("8-5-4-2-A", low.value.to_string());
+    }
+
+    #[test]
+    fn omaha_no_low() {
+        // Only one low card on the board, the low needs three
+        let split = omaha_hi_lo(&cards("As 2d Kc Kh"), &cards("3s 9h Tc Jd Qs")).unwrap();
+
+        assert_eq!(None, split.low);
+        assert_eq!(HandCategory::Straight, split.high.value.category());
+    }
+
+    #[test]
+    fn wrong_counts() {
+        assert_eq!(
+            Err(PokerError::WrongCardCount { count: 4, min: 5, max: 7 }),
+            hi_lo(&cards("As 2d 3h 4c"))
+        );
+        assert!(omaha_hi_lo(&cards("As"), &cards("2d 3h 4c")).is_err());
+        assert!(omaha_hi_lo(&cards("As Kd"), &cards("2d 3h")).is_err());
+    }
+}
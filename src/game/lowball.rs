@@ -0,0 +1,209 @@
+use crate::game::eval;
+use crate::game::hand::{HandCategory, HandValue};
+use crate::{Card, Rank};
+use std::cmp::Ordering;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Lowball {
+    // The ace is low and straights and flushes don't count, so the
+    // best hand is 5-4-3-2-A
+    AceToFive,
+    // The ace is high and straights and flushes count against the
+    // hand, so the best hand is 7-5-4-3-2 of mixed suits
+    DeuceToSeven,
+}
+
+impl Lowball {
+    pub fn evaluate(&self, cards: &[Card; 5]) -> LowValue {
+        match self {
+            Lowball::AceToFive => ace_to_five(cards),
+            Lowball::DeuceToSeven => deuce_to_seven(cards),
+        }
+    }
+}
+
+// Strength of a lowball hand. It wraps the value of the hand as a high
+// hand and compares the other way around, so like with HandValue the
+// better hand is the greater one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct LowValue(HandValue);
+
+impl LowValue {
+    // What the hand makes as a high hand: only high card hands are
+    // good low hands
+    pub fn category(&self) -> HandCategory {
+        self.0.category()
+    }
+
+    // Ranks from the one that matters most, the ace last when it's low
+    pub fn ranks(&self) -> Vec<Rank> {
+        self.0.ranks()
+    }
+
+    // Whether the hand is a low of the given rank or better, as in
+    // 8 or better: no pairs and no card above the rank. Aces count
+    // low in ace to five hands.
+    pub fn qualifies(&self, max: Rank) -> bool {
+        self.category() == HandCategory::HighCard && self.0.top_value() <= max.value()
+    }
+}
+
+impl Ord for LowValue {
+    fn cmp(&self, other: &LowValue) -> Ordering {
+        other.0.cmp(&self.0)
+    }
+}
+
+impl PartialOrd for LowValue {
+    fn partial_cmp(&self, other: &LowValue) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// Lowball hands are read from the highest card down, as in 7-5-4-3-2
+impl fmt::Display for LowValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let ranks: Vec<String> = self.ranks().iter().map(|r| r.letter().to_string()).collect();
+
+        if self.category() == HandCategory::HighCard {
+            return f.write_str(&ranks.join("-"));
+        }
+
+        write!(f, "{} ({})", self.category(), ranks.join(" "))
+    }
+}
+
+pub fn ace_to_five(cards: &[Card; 5]) -> LowValue {
+    // Rank values with the ace as 1, counted by how many times they
+    // repeat, bigger groups first and higher ranks first among them
+    let mut counts = [0u8; 14];
+    for card in cards {
+        let value = match card.rank {
+            Rank::Ace => 1,
+            rank => rank.value(),
+        };
+        counts[value as usize] += 1;
+    }
+
+    let mut groups: Vec<(u8, u8)> = (1..14u8)
+        .filter(|v| counts[*v as usize] > 0)
+        .map(|v| (counts[v as usize], v))
+        .collect();
+    groups.sort_unstable_by(|a, b| b.cmp(a));
+
+    let rank_keys: Vec<u8> = groups.iter().map(|g| g.1).collect();
+    let ranks_count: Vec<u8> = groups.iter().map(|g| g.0).collect();
+
+    let category = match &ranks_count[..] {
+        [5] => HandCategory::FiveOfAKind,
+        [4, ..] => HandCategory::FourOfAKind,
+        [3, 2] => HandCategory::FullHouse,
+        [3, ..] => HandCategory::ThreeOfAKind,
+        [2, 2, ..] => HandCategory::TwoPair,
+        [2, ..] => HandCategory::Pair,
+        _ => HandCategory::HighCard,
+    };
+
+    LowValue(HandValue::new(category, &rank_keys))
+}
+
+pub fn deuce_to_seven(cards: &[Card; 5]) -> LowValue {
+    let value = eval::evaluate(cards);
+
+    // The ace is always high, so the wheel isn't a straight but an
+    // ace high hand
+    let wheel = matches!(value.category(), HandCategory::Straight | HandCategory::StraightFlush)
+        && value.high_rank() == Rank::Five;
+
+    if wheel {
+        let category = match value.category() {
+            HandCategory::StraightFlush => HandCategory::Flush,
+            _ => HandCategory::HighCard,
+        };
+
+        return LowValue(HandValue::new(category, &[14, 5, 4, 3, 2]));
+    }
+
+    LowValue(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_hand;
+
+    fn hand(s: &str) -> [Card; 5] {
+        parse_hand(s).unwrap().try_into().unwrap()
+    }
+
+    fn a5(s: &str) -> LowValue {
+        ace_to_five(&hand(s))
+    }
+
+    fn d7(s: &str) -> LowValue {
+        deuce_to_seven(&hand(s))
+    }
+
+    #[test]
+    fn ace_to_five_order() {
+        // The wheel is the best hand, straights and flushes don't count
+        assert!(a5("5h 4h 3h 2h Ah") > a5("6c 4d 3h 2s As"));
+        assert_eq!(a5("5h 4h 3h 2h Ah"), a5("5c 4d 3h 2s As"));
+
+        // The highest card decides first, then the next one
+        assert!(a5("6c 4d 3h 2s As") > a5("6c 5d 3h 2s As"));
+        assert!(a5("8c 7d 6h 5s 4s") > a5("9c 4d 3h 2s As"));
+        assert!(a5("Kc Qd Jh Ts 9s") > a5("Ac Ad 2h 3s 4s"));
+
+        // Pairs are worse than any high card hand, and lower pairs are better
+        assert!(a5("Ac Ad 2h 3s 4s") > a5("2c 2d 3h 4s 5s"));
+        assert!(a5("3c 3d 2h 2s 4s") < a5("Kc Kd 2h 3s 4s"));
+        assert!(a5("5c 5d 5h 2s 3s") < a5("Qc Qd 2h 2s 3s"));
+    }
+
+    #[test]
+    fn ace_to_five_display() {
+        assert_eq!("5-4-3-2-A", a5("Ah 2d 3c 4s 5h").to_string());
+        assert_eq!(HandCategory::Pair, a5("Ah Ad 3c 4s 5h").category());
+        assert_eq!(Rank::Ace, a5("Ah Ad 3c 4s 5h").ranks()[0]);
+    }
+
+    #[test]
+    fn qualifiers() {
+        assert!(a5("8c 7d 6h 5s 4s").qualifies(Rank::Eight));
+        assert!(a5("Ac 2d 3h 4s 8s").qualifies(Rank::Eight));
+        assert!(!a5("9c 4d 3h 2s As").qualifies(Rank::Eight));
+        assert!(!a5("Ac Ad 3h 4s 5s").qualifies(Rank::Eight));
+        assert!(!d7("Ac 2d 3h 4s 7s").qualifies(Rank::Eight));
+        assert!(d7("7c 5d 4h 3s 2s").qualifies(Rank::Seven));
+    }
+
+    #[test]
+    fn deuce_to_seven_order() {
+        // 7-5-4-3-2 is the best hand
+        assert!(d7("7c 5d 4h 3s 2s") > d7("7c 6d 4h 3s 2s"));
+        assert!(d7("7c 6d 4h 3s 2s") > d7("8c 5d 4h 3s 2s"));
+
+        // Straights and flushes count against the hand
+        assert!(d7("8c 5d 4h 3s 2s") > d7("6c 5d 4h 3s 2s"));
+        assert!(d7("Kc Qd Jh 9s 8s") > d7("7s 5s 4s 3s 2s"));
+        assert_eq!(HandCategory::Straight, d7("6c 5d 4h 3s 2s").category());
+
+        // The ace is high and the wheel isn't a straight
+        assert_eq!(HandCategory::HighCard, d7("Ac 5d 4h 3s 2s").category());
+        assert!(d7("Kc 5d 4h 3s 2s") > d7("Ac 5d 4h 3s 2s"));
+        assert!(d7("Ac 5d 4h 3s 2s") > d7("2c 2d 4h 3s 5s"));
+        assert_eq!(HandCategory::Flush, d7("As 5s 4s 3s 2s").category());
+        assert_eq!("A-5-4-3-2", d7("Ac 5d 4h 3s 2s").to_string());
+    }
+
+    #[test]
+    fn lowball_evaluate() {
+        let wheel = hand("Ac 2d 3h 4s 5s");
+        let seven = hand("7c 5d 4h 3s 2s");
+
+        assert!(Lowball::AceToFive.evaluate(&wheel) > Lowball::AceToFive.evaluate(&seven));
+        assert!(Lowball::DeuceToSeven.evaluate(&wheel) < Lowball::DeuceToSeven.evaluate(&seven));
+    }
+}
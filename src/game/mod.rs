@@ -1 +1,14 @@
+pub mod advisor;
+pub mod deck;
+pub mod error;
+pub mod eval;
+pub mod hand;
+pub mod hilo;
+pub mod lowball;
+pub mod paytable;
 pub mod poker;
+pub mod rules;
+pub mod sim;
+pub mod state;
+pub mod strategy;
+pub mod trainer;
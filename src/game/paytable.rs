@@ -0,0 +1,350 @@
+use crate::game::hand::{HandCategory, HandValue};
+use crate::Rank;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+// Points paid for each hand category. Pay tables can be one of the
+// built-in presets or loaded from a file with one `key = value` pair
+// per line, which is also valid TOML:
+//
+//     # Comments start with a hash
+//     name = "Jacks or Better 9/6"
+//     min_pair = "J"
+//     pair = 1
+//     two_pair = 2
+//     three_of_a_kind = 3
+//     straight = 4
+//     flush = 6
+//     full_house = 9
+//     four_of_a_kind = 25
+//     straight_flush = 50
+//     royal_flush = 250
+//
+// Categories that aren't listed pay nothing. `min_pair` is the lowest
+// pair that pays, every pair pays when it's missing.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PayTable {
+    pub name: String,
+    pub min_pair: Rank,
+    payouts: [i32; HandCategory::ALL.len()],
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PayTableError {
+    Io(String),
+    Parse { line: usize, message: String },
+}
+
+impl fmt::Display for PayTableError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PayTableError::Io(e) => write!(f, "Couldn't read pay table: {}", e),
+            PayTableError::Parse { line, message } => {
+                write!(f, "Pay table error on line {}: {}", line, message)
+            }
+        }
+    }
+}
+
+impl Error for PayTableError {}
+
+impl PayTable {
+    pub fn new(name: &str, min_pair: Rank, payouts: [i32; HandCategory::ALL.len()]) -> PayTable {
+        PayTable {
+            name: name.to_string(),
+            min_pair,
+            payouts,
+        }
+    }
+
+    // The original payouts of the game. Wild royal flushes pay the
+    // same as natural ones.
+    pub fn standard() -> PayTable {
+        PayTable::new("Standard", Rank::Two, [0, 1, 3, 5, 10, 15, 18, 20, 30, 35, 40, 40])
+    }
+
+    // Classic full pay Jacks or Better, paid per coin with one coin bet.
+    // It's played without wild cards.
+    pub fn jacks_or_better() -> PayTable {
+        PayTable::new("Jacks or Better 9/6", Rank::Jack, [0, 1, 2, 3, 4, 6, 9, 25, 50, 0, 0, 250])
+    }
+
+    // Deuces Wild paid per coin with one coin bet. Pairs and two pairs
+    // don't pay. Unlike full pay there's no bonus for four deuces, they
+    // score as the wild royal flush or five of a kind they make. The
+    // deuces are only wild when the rules say so, `--paytable
+    // deuces-wild` makes them wild unless `--wild` picks another rank.
+    pub fn deuces_wild() -> PayTable {
+        PayTable::new("Deuces Wild (no deuces bonus)", Rank::Two, [0, 0, 0, 1, 2, 2, 3, 5, 9, 15, 25, 250])
+    }
+
+    // Jacks or better for the short deck, where flushes are rarer
+    // than full houses and pay more
+    pub fn short_deck() -> PayTable {
+        PayTable::new("Short Deck", Rank::Jack, [0, 1, 2, 3, 4, 9, 6, 25, 50, 60, 80, 250])
+    }
+
+    pub fn presets() -> Vec<(&'static str, PayTable)> {
+        vec![
+            ("standard", PayTable::standard()),
+            ("jacks-or-better", PayTable::jacks_or_better()),
+            ("deuces-wild", PayTable::deuces_wild()),
+            ("short-deck", PayTable::short_deck()),
+        ]
+    }
+
+    pub fn preset(key: &str) -> Option<PayTable> {
+        PayTable::presets()
+            .into_iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, table)| table)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<PayTable, PayTableError> {
+        let contents = fs::read_to_string(path).map_err(|e| PayTableError::Io(e.to_string()))?;
+
+        PayTable::parse(&contents)
+    }
+
+    pub fn parse(contents: &str) -> Result<PayTable, PayTableError> {
+        let mut table = PayTable::new("Custom", Rank::Two, [0; HandCategory::ALL.len()]);
+
+        for (n, line) in contents.lines().enumerate() {
+            let error = |message: String| PayTableError::Parse { line: n + 1, message };
+            let line = strip_comment(line);
+
+            // Blank lines and TOML table headers are skipped
+            if line.is_empty() || line.starts_with('[') {
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| error(format!("expected `key = value`, got \"{}\"", line)))?;
+            let key = key.trim();
+            let value = value.trim().trim_matches('"');
+
+            match key {
+                "name" => table.name = value.to_string(),
+                "min_pair" => {
+                    table.min_pair = value
+                        .parse()
+                        .map_err(|_| error(format!("invalid rank \"{}\"", value)))?
+                }
+                _ => {
+                    let category = HandCategory::ALL
+                        .into_iter()
+                        .find(|c| category_key(*c) == key)
+                        .ok_or_else(|| error(format!("unknown hand \"{}\"", key)))?;
+                    let points = value
+                        .parse::<i32>()
+                        .ok()
+                        .filter(|p| *p >= 0)
+                        .ok_or_else(|| error(format!("invalid points \"{}\"", value)))?;
+
+                    table.payouts[category as usize] = points;
+                }
+            }
+        }
+
+        Ok(table)
+    }
+
+    pub fn payout(&self, category: HandCategory) -> i32 {
+        self.payouts[category as usize]
+    }
+
+    // Points won by a hand
+    pub fn points(&self, value: HandValue) -> i32 {
+        if value.category() == HandCategory::Pair && value.high_rank() < self.min_pair {
+            return 0;
+        }
+
+        self.payout(value.category())
+    }
+
+    // Tells tables apart by their contents, as custom tables can share
+    // a name and pay differently. It's a hash of the table as written
+    // to a file (FNV-1a, which stays the same on every release).
+    pub fn id(&self) -> String {
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+
+        for byte in self.to_file_string().bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+
+        format!("{:016x}", hash)
+    }
+
+    // Id of the preset with the name, for results saved before the ids
+    // were kept
+    pub fn preset_id(name: &str) -> Option<String> {
+        PayTable::presets()
+            .into_iter()
+            .find(|(_, table)| table.name == name)
+            .map(|(_, table)| table.id())
+    }
+
+    // Writes the table in the same format `parse` reads
+    pub fn to_file_string(&self) -> String {
+        let mut s = format!("name = \"{}\"\nmin_pair = \"{}\"\n", self.name, self.min_pair.letter());
+
+        for category in HandCategory::ALL {
+            if self.payout(category) > 0 {
+                s.push_str(&format!("{} = {}\n", category_key(category), self.payout(category)));
+            }
+        }
+
+        s
+    }
+}
+
+impl Default for PayTable {
+    fn default() -> PayTable {
+        PayTable::standard()
+    }
+}
+
+// The line without its comment, if it has one. A # only starts a
+// comment outside of quotes, so names can hold one.
+pub(crate) fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '#' if !quoted => return line[..i].trim(),
+            _ => {}
+        }
+    }
+
+    line.trim()
+}
+
+fn category_key(category: HandCategory) -> &'static str {
+    match category {
+        HandCategory::HighCard => "high_card",
+        HandCategory::Pair => "pair",
+        HandCategory::TwoPair => "two_pair",
+        HandCategory::ThreeOfAKind => "three_of_a_kind",
+        HandCategory::Straight => "straight",
+        HandCategory::Flush => "flush",
+        HandCategory::FullHouse => "full_house",
+        HandCategory::FourOfAKind => "four_of_a_kind",
+        HandCategory::StraightFlush => "straight_flush",
+        HandCategory::FiveOfAKind => "five_of_a_kind",
+        HandCategory::WildRoyalFlush => "wild_royal_flush",
+        HandCategory::RoyalFlush => "royal_flush",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::poker::check_hand;
+    use crate::parse_hand;
+
+    fn value(s: &str) -> HandValue {
+        check_hand(&parse_hand(s).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn standard_points() {
+        let table = PayTable::standard();
+
+        assert_eq!(0, table.points(value("Ts 8s 3c 4h Kh")));
+        assert_eq!(1, table.points(value("2s 2h 5h Ah 6c")));
+        assert_eq!(3, table.points(value("Ks Qc Qh Kh 2s")));
+        assert_eq!(18, table.points(value("5c 5s Ah As Ad")));
+        assert_eq!(40, table.points(value("Kc Qc Tc Ac Jc")));
+    }
+
+    #[test]
+    fn jacks_or_better_points() {
+        let table = PayTable::jacks_or_better();
+
+        // Low pairs don't pay
+        assert_eq!(0, table.points(value("Ts Th 5h Ah 6c")));
+        assert_eq!(1, table.points(value("Js Jh 5h Ah 6c")));
+        assert_eq!(1, table.points(value("As Ah 5h Qh 6c")));
+        assert_eq!(9, table.points(value("5c 5s Ah As Ad")));
+        assert_eq!(250, table.points(value("Kc Qc Tc Ac Jc")));
+    }
+
+    #[test]
+    fn presets() {
+        assert_eq!(Some(PayTable::standard()), PayTable::preset("standard"));
+        assert_eq!(Some(PayTable::jacks_or_better()), PayTable::preset("jacks-or-better"));
+        assert_eq!(Some(PayTable::short_deck()), PayTable::preset("short-deck"));
+        assert_eq!(Some(PayTable::deuces_wild()), PayTable::preset("deuces-wild"));
+        assert_eq!(None, PayTable::preset("missing"));
+    }
+
+    #[test]
+    fn parse_file() {
+        let table = PayTable::parse(
+            "# Bonus table\n\
+             [paytable]\n\
+             name = \"Bonus\"\n\
+             min_pair = \"Q\"\n\
+             pair = 2 # per coin\n\
+             \n\
+             royal_flush = 800\n",
+        )
+        .unwrap();
+
+        assert_eq!("Bonus", table.name);
+        assert_eq!(Rank::Queen, table.min_pair);
+        assert_eq!(2, table.payout(HandCategory::Pair));
+        assert_eq!(800, table.payout(HandCategory::RoyalFlush));
+        assert_eq!(0, table.payout(HandCategory::Flush));
+    }
+
+    #[test]
+    fn comments() {
+        assert_eq!("pair = 2", strip_comment("  pair = 2 # per coin"));
+        assert_eq!("", strip_comment("# name = \"Bonus\""));
+        assert_eq!("name = \"Bonus #2\"", strip_comment("name = \"Bonus #2\" # the second"));
+
+        let table = PayTable::parse("name = \"Bonus #2\"\npair = 1").unwrap();
+        assert_eq!("Bonus #2", table.name);
+        assert_eq!(Ok(table.clone()), PayTable::parse(&table.to_file_string()));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            Err(PayTableError::Parse { line: 2, message: "unknown hand \"pairs\"".to_string() }),
+            PayTable::parse("pair = 1\npairs = 2")
+        );
+        assert_eq!(
+            Err(PayTableError::Parse { line: 1, message: "invalid points \"-3\"".to_string() }),
+            PayTable::parse("flush = -3")
+        );
+        assert!(PayTable::parse("flush 3").is_err());
+        assert!(PayTable::parse("min_pair = \"X\"").is_err());
+    }
+
+    #[test]
+    fn ids() {
+        let standard = PayTable::standard();
+        let mut renamed = PayTable::jacks_or_better();
+        renamed.name = standard.name.clone();
+
+        assert_eq!("67516319911d8963", standard.id());
+        assert_eq!(standard.id(), PayTable::standard().id());
+        assert_ne!(standard.id(), renamed.id());
+        assert_eq!(Some(standard.id()), PayTable::preset_id("Standard"));
+        assert_eq!(None, PayTable::preset_id("Custom"));
+    }
+
+    #[test]
+    fn file_round_trip() {
+        for (_, table) in PayTable::presets() {
+            assert_eq!(Ok(table.clone()), PayTable::parse(&table.to_file_string()));
+        }
+    }
+}
@@ -1,174 +1,99 @@
-use rand::seq::SliceRandom;
-use rand::thread_rng;
-use single_player_poker::Card;
-use std::collections::HashMap;
-use std::process;
-
-pub fn change_cards(deck: &mut Vec<u8>, hand: &mut Vec<Card>, to_change: &Vec<usize>) -> Vec<u8> {
-    let mut discarded: Vec<u8> = vec![];
-
-    // Removed cards are sent to the discarded pile
-    // New cards are popped from the deck
-    for i in to_change {
-        discarded.push(hand.remove(*i).value);
-        let new_card = deck.pop().unwrap_or_else(|| {
-            eprintln!("Problem extracting card from deck");
-            process::exit(1);
+use crate::game::deck::Deck;
+use crate::game::error::PokerError;
+use crate::game::eval;
+use crate::game::hand::{BestHand, HandValue};
+use crate::game::lowball::{Lowball, LowValue};
+use crate::game::rules::Rules;
+use crate::Card;
+use rand::Rng;
+
+// Most cards the player can change in a hand
+pub const MAX_CHANGES: usize = 3;
+
+pub fn change_cards(
+    deck: &mut Deck,
+    hand: &mut [Card],
+    to_change: &[usize],
+) -> Result<Vec<Card>, PokerError> {
+    if to_change.len() > MAX_CHANGES {
+        return Err(PokerError::TooManyChanges {
+            requested: to_change.len(),
+            max: MAX_CHANGES,
         });
-        hand.insert(*i, Card::new(new_card));
     }
 
-    return discarded;
+    // Removed cards are sent to the discarded pile
+    // New cards are taken from the deck
+    deck.replace(hand, to_change)
 }
 
-pub fn check_hand(hand: &Vec<Card>) -> i32 {
-    let mut suits = HashMap::new();
-    let mut ranks = HashMap::new();
-    let mut rank_keys: Vec<u8> = vec![];
-    let mut ranks_count: Vec<i32> = vec![];
-
-    for card in hand {
-        let suit_counter = suits.entry(&card.suit).or_insert(0);
-        let rank_counter = ranks.entry(&card.rank).or_insert(0);
-
-        *suit_counter += 1;
-        *rank_counter += 1;
-    }
-
-    let ranks_iter = ranks.into_iter();
-
-    for rank in ranks_iter {
-        // rank_keys represents the ranks themselves
-        // in a vec
-        rank_keys.push(*rank.0);
-
-        // ranks_count represents how many times
-        // each rank repeated
-        ranks_count.push(rank.1);
-    }
-
-    // Four of a kind
-    if ranks_count.contains(&4) {
-        return 20;
-    }
-
-    // Full house
-
-    // Four of a kind is the only other way
-    // to have no more than 2 different ranks
-    if ranks_count.len() == 2 {
-        return 18;
-    }
-
-    // Flush
-    let suit_keys: Vec<&String> = suits.into_keys().collect();
-    let flush_found = suit_keys.len() == 1;
-
-    // Straight
-    let mut straight_found = false;
-    if rank_keys.len() == 5 {
-        rank_keys.sort();
-        straight_found = straight(&rank_keys[..]);
-
-        // If not found and there's an ace in the hand
-        // check again counting the ace as its high value
-        if !straight_found && rank_keys.contains(&1) {
-            rank_keys.push(14);
-            straight_found = straight(&rank_keys[1..]);
-        }
-    }
-
-    // Match to find straight flush, just flush or just straight
-    match (flush_found, straight_found) {
-        (false, false) => {}
-        (true, false) => return 15, // Flush
-        (false, true) => return 10, // Straight
-        (true, true) => {
-            if rank_keys[rank_keys.len() - 1] == 14 {
-                return 40; // Royal Flush
-            }
-
-            return 30; // Straight Flush
-        }
-    }
-
-    // Three of a kind
-    if ranks_count.contains(&3) {
-        return 5;
-    }
+pub fn check_hand(hand: &[Card]) -> Result<HandValue, PokerError> {
+    check_hand_with(hand, &Rules::default())
+}
 
-    let mut count_pairs = 0;
-    for r in ranks_count {
-        if r == 2 {
-            count_pairs += 1;
-        }
-    }
+// Evaluates the hand with the wild cards of the rules
+pub fn check_hand_with(hand: &[Card], rules: &Rules) -> Result<HandValue, PokerError> {
+    let cards: &[Card; 5] = hand
+        .try_into()
+        .map_err(|_| PokerError::WrongHandSize(hand.len()))?;
 
-    // Pairs
-    match count_pairs {
-        1 => return 1, // Pair
-        2 => return 3, // Two pair
-        _ => return 0, // Nothing
-    };
+    Ok(rules.evaluate(cards))
 }
 
-pub fn deal(deck: &mut Vec<u8>) -> Vec<Card> {
-    let mut cards: Vec<Card> = vec![];
-    let mut rng = thread_rng();
-
-    deck.shuffle(&mut rng);
+// Evaluates the hand as a lowball hand
+pub fn check_low_hand(hand: &[Card], lowball: Lowball) -> Result<LowValue, PokerError> {
+    let cards: &[Card; 5] = hand
+        .try_into()
+        .map_err(|_| PokerError::WrongHandSize(hand.len()))?;
 
-    for _i in 0..5 {
-        let card_val = deck.pop().unwrap_or_else(|| {
-            eprintln!("Problem extracting card from deck");
-            process::exit(1);
-        });
-        cards.push(Card::new(card_val));
-    }
-
-    return cards;
+    Ok(lowball.evaluate(cards))
 }
 
-pub fn generate_deck() -> Vec<u8> {
-    return (1..53).collect::<Vec<u8>>();
+// Best 5 card hand out of 5 to 7 cards
+pub fn best_hand(cards: &[Card]) -> Result<BestHand, PokerError> {
+    eval::best_five(cards).ok_or(PokerError::WrongCardCount {
+        count: cards.len(),
+        min: 5,
+        max: 7,
+    })
 }
 
-pub fn reset_deck(deck: &mut Vec<u8>, hand: &mut Vec<Card>, discarded: &mut Vec<u8>) {
-    deck.append(discarded);
-
-    for card in hand {
-        deck.push(card.value);
-    }
+// Shuffles the deck with the given random number generator and deals
+// a hand. The same generator state deals the same hand.
+pub fn deal<R: Rng + ?Sized>(deck: &mut Deck, rng: &mut R) -> Result<Vec<Card>, PokerError> {
+    deck.shuffle(rng);
 
-    discarded.clear();
+    deck.deal(5)
 }
 
-pub fn straight(hand: &[u8]) -> bool {
-    let mut past = hand[0] - 1;
-
-    for card in hand {
-        if past == card - 1 {
-            past = *card;
-        } else {
-            return false;
-        }
-    }
-
-    return true;
+pub fn reset_deck(deck: &mut Deck) {
+    deck.recollect();
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::game::hand::HandCategory;
+    use crate::parse_hand;
+    use rand::rngs::StdRng;
+    use rand::{thread_rng, SeedableRng};
+
+    fn hand(s: &str) -> Vec<Card> {
+        parse_hand(s).unwrap()
+    }
+
+    fn category(s: &str) -> HandCategory {
+        check_hand(&hand(s)).unwrap().category()
+    }
 
     // Functions tests
     #[test]
     fn test_change() {
-        let mut deck = generate_deck();
-        let mut hand = deal(&mut deck);
+        let mut deck = Deck::new();
+        let mut hand = deal(&mut deck, &mut thread_rng()).unwrap();
         let hand_copy = hand.clone();
         let to_change: Vec<usize> = vec![0, 1, 4];
-        let _discarded = change_cards(&mut deck, &mut hand, &to_change);
+        let discarded = change_cards(&mut deck, &mut hand, &to_change).unwrap();
 
         // Deck has 5 cards removed from the dealing and
         // 3 more after changing cards
@@ -176,12 +101,13 @@ mod tests {
 
         // hand cards should be different after changing
         assert_ne!(hand_copy, hand);
+        assert_eq!(vec![hand_copy[0], hand_copy[1], hand_copy[4]], discarded);
     }
 
     #[test]
     fn test_deal() {
-        let mut deck = generate_deck();
-        let hand = deal(&mut deck);
+        let mut deck = Deck::new();
+        let hand = deal(&mut deck, &mut thread_rng()).unwrap();
 
         // Hands always contain 5 random cards
         assert_eq!(5, hand.len());
@@ -192,198 +118,212 @@ mod tests {
     }
 
     #[test]
-    fn test_reset() {
-        let mut deck = generate_deck();
-        let mut hand = deal(&mut deck);
-        let to_change: Vec<usize> = vec![1, 2, 3];
-        let mut discarded = change_cards(&mut deck, &mut hand, &to_change);
+    fn test_change_errors() {
+        let mut deck = Deck::new();
+        let mut hand = deal(&mut deck, &mut thread_rng()).unwrap();
+        let hand_copy = hand.clone();
 
-        reset_deck(&mut deck, &mut hand, &mut discarded);
-        deck.sort();
+        assert_eq!(
+            Err(PokerError::TooManyChanges { requested: 4, max: 3 }),
+            change_cards(&mut deck, &mut hand, &[0, 1, 2, 3])
+        );
+        assert_eq!(
+            Err(PokerError::InvalidIndex(7)),
+            change_cards(&mut deck, &mut hand, &[7])
+        );
+
+        // Nothing changes after an error
+        assert_eq!(hand_copy, hand);
+        assert_eq!(47, deck.len());
+    }
 
-        let deck2 = generate_deck();
+    #[test]
+    fn test_deal_seeded() {
+        let mut deck = Deck::new();
+        let mut rng = StdRng::seed_from_u64(42);
+
+        assert_eq!(hand("6d 5c Ac As 3d"), deal(&mut deck, &mut rng).unwrap());
+
+        // The same seed deals the same hands, even after changing cards
+        let play = |seed| {
+            let mut deck = Deck::new();
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut hands = vec![];
+
+            for _ in 0..3 {
+                let mut hand = deal(&mut deck, &mut rng).unwrap();
+                change_cards(&mut deck, &mut hand, &[0, 2]).unwrap();
+                hands.push(hand);
+                reset_deck(&mut deck);
+            }
 
-        // After reset, deck should contain the same
-        // values it had when created
-        assert_eq!(deck2, deck);
+            hands
+        };
 
-        // The discarded pile is cleared
-        assert_eq!(0, discarded.len());
+        assert_eq!(play(7), play(7));
+        assert_ne!(play(7), play(8));
+    }
+
+    #[test]
+    fn test_deal_empty_deck() {
+        let mut deck = Deck::new();
+        deck.deal(50).unwrap();
+
+        assert_eq!(
+            Err(PokerError::NotEnoughCards { requested: 5, remaining: 2 }),
+            deal(&mut deck, &mut thread_rng())
+        );
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut deck = Deck::new();
+        let mut hand = deal(&mut deck, &mut thread_rng()).unwrap();
+        let to_change: Vec<usize> = vec![1, 2, 3];
+        change_cards(&mut deck, &mut hand, &to_change).unwrap();
+
+        reset_deck(&mut deck);
+
+        // After reset, deck should contain every card again
+        // and the discarded pile is cleared
+        assert_eq!(52, deck.len());
+        assert_eq!(0, deck.discards().len());
     }
 
     // Hand combinations tests
     #[test]
     fn hand_nothing() {
-        let card1 = Card::new(10);
-        let card2 = Card::new(8);
-        let card3 = Card::new(42);
-        let card4 = Card::new(17);
-        let card5 = Card::new(26);
-        let hand = vec![card1, card2, card3, card4, card5];
-
-        assert_eq!(0, check_hand(&hand));
+        assert_eq!(HandCategory::HighCard, category("Ts 8s 3c 4h Kh"));
     }
 
     #[test]
     fn hand_pair() {
-        let ace_one = Card::new(1); // Ace of spades
-        let ace_two = Card::new(14); // Ace of hearts
-        let card3 = Card::new(4);
-        let card4 = Card::new(18);
-        let card5 = Card::new(45);
-        let hand = vec![ace_one, card3, card4, ace_two, card5];
-
-        // A pair returns 1 point
-        assert_eq!(1, check_hand(&hand));
+        assert_eq!(HandCategory::Pair, category("As 4s 5h Ah 6c"));
     }
 
     #[test]
     fn hand_two_pair() {
-        let k_one = Card::new(13); // K of spades
-        let k_two = Card::new(26); // K of hearts
-        let q_one = Card::new(51); // Q of clubs
-        let q_two = Card::new(25); // Q of hearts
-        let card5 = Card::new(2);
-        let hand = vec![k_one, q_one, q_two, k_two, card5];
-
-        // Two pairs return 3 points
-        assert_eq!(3, check_hand(&hand));
+        assert_eq!(HandCategory::TwoPair, category("Ks Qc Qh Kh 2s"));
     }
 
     #[test]
     fn hand_three_of_a_kind() {
-        let five_one = Card::new(5); // 5 of spades
-        let five_two = Card::new(31); // 5 of diamonds
-        let five_three = Card::new(44); // 5 of clubs
-        let card4 = Card::new(25);
-        let card5 = Card::new(47);
-        let hand = vec![five_one, card4, five_two, card5, five_three];
-
-        // Three of a kind return 5 points
-        assert_eq!(5, check_hand(&hand));
+        assert_eq!(HandCategory::ThreeOfAKind, category("5s Qh 5d 8c 5c"));
     }
 
     #[test]
     fn hand_straight() {
         // First straight starts with ace and ends in 5
-        let card1 = Card::new(1);
-        let card2 = Card::new(15);
-        let card3 = Card::new(29);
-        let card4 = Card::new(43);
-        let card5 = Card::new(44);
-        let hand = vec![card1, card4, card2, card5, card3];
-
-        assert_eq!(10, check_hand(&hand));
+        assert_eq!(HandCategory::Straight, category("As 4c 2h 5c 3d"));
 
         // Second straight starts with 10 and ends in A
-        let card1 = Card::new(23);
-        let card2 = Card::new(24);
-        let card3 = Card::new(25);
-        let card4 = Card::new(26);
-        let card5 = Card::new(1);
-        let hand2 = vec![card5, card2, card1, card4, card3];
-
-        assert_eq!(10, check_hand(&hand2));
+        assert_eq!(HandCategory::Straight, category("As Jh Th Kh Qh"));
 
         // Third hand doesn't have a straight
-        let card1 = Card::new(2);
-        let card2 = Card::new(3);
-        let card3 = Card::new(4);
-        let card4 = Card::new(5);
-        let card5 = Card::new(5);
-        let hand3 = vec![card5, card4, card2, card1, card3];
-
-        assert_ne!(10, check_hand(&hand3));
+        assert_ne!(HandCategory::Straight, category("5s 5s 3s 2s 4s"));
 
         // Fourth hand doesn't have a straight
-        let card1 = Card::new(11);
-        let card2 = Card::new(12);
-        let card3 = Card::new(13);
-        let card4 = Card::new(14);
-        let card5 = Card::new(15);
-        let hand4 = vec![card5, card4, card2, card1, card3];
-
-        assert_ne!(10, check_hand(&hand4));
+        assert_ne!(HandCategory::Straight, category("2h Ah Qs Js Ks"));
     }
 
     #[test]
     fn hand_flush() {
         // All have the spades suit
-        let card1 = Card::new(1);
-        let card2 = Card::new(2);
-        let card3 = Card::new(5);
-        let card4 = Card::new(10);
-        let card5 = Card::new(13);
-        let hand = vec![card5, card4, card2, card1, card3];
-
-        assert_eq!(15, check_hand(&hand));
+        assert_eq!(HandCategory::Flush, category("Ks Ts 2s As 5s"));
     }
 
     #[test]
     fn hand_full_house() {
-        // All with the spades suit
-        let card1 = Card::new(1); // A of spades
-        let card2 = Card::new(14); // A of hearts
-        let card3 = Card::new(27); // A of diamonds
-        let card4 = Card::new(5); // 5 of spades
-        let card5 = Card::new(44); // 5 of clubs
-        let hand = vec![card5, card4, card2, card1, card3];
-
-        assert_eq!(18, check_hand(&hand));
-
-        let card1 = Card::new(1); // A of spades
-        let card2 = Card::new(14); // A of hearts
-        let card3 = Card::new(27); // A of diamonds
-        let card4 = Card::new(40); // A of clubs
-        let card5 = Card::new(44); // 5 of clubs
-        let hand = vec![card5, card4, card2, card1, card3];
-
-        assert_ne!(18, check_hand(&hand)); // Four of a kind is returned
+        assert_eq!(HandCategory::FullHouse, category("5c 5s Ah As Ad"));
+
+        // Four of a kind is returned
+        assert_ne!(HandCategory::FullHouse, category("5c Ac Ah As Ad"));
     }
 
     #[test]
     fn hand_four_of_a_kind() {
-        let j_one = Card::new(11); // J of spades
-        let j_two = Card::new(24); // J of hears
-        let j_three = Card::new(37); // J of diamonds
-        let j_four = Card::new(50); // J of clubs
-        let card5 = Card::new(4);
-        let hand = vec![j_one, j_two, j_three, card5, j_four];
-
-        // Four of a kind return 20 points
-        assert_eq!(20, check_hand(&hand));
+        assert_eq!(HandCategory::FourOfAKind, category("Js Jh Jd 4s Jc"));
     }
 
     #[test]
     fn hand_straight_flush() {
-        let card1 = Card::new(16); // 3 of hearts
-        let card2 = Card::new(17); // 4 of hearts
-        let card3 = Card::new(18); // 5 of heats
-        let card4 = Card::new(19); // 6 of hearts
-        let card5 = Card::new(20); // 7 of hearts
-        let hand = vec![card5, card4, card2, card1, card3];
-
-        assert_eq!(30, check_hand(&hand));
+        assert_eq!(HandCategory::StraightFlush, category("7h 6h 4h 3h 5h"));
+    }
+
+    #[test]
+    fn hand_kickers() {
+        let value = |s| check_hand(&hand(s)).unwrap();
+
+        // Same pair, the kickers decide
+        assert!(value("As Ah 9c 7d 3s") > value("Ac Ad 9s 7h 2s"));
+        // Suits don't break ties
+        assert_eq!(value("As Ah 9c 7d 3s"), value("Ac Ad 9s 7h 3c"));
+        // The higher pair wins over better kickers
+        assert!(value("3s 3h 4c 5d 6s") > value("2c 2d As Kh Qs"));
+        // Two pair is decided by the top pair, then the second, then the kicker
+        assert!(value("Ks Kh 2c 2d 3s") > value("Qc Qd Jh Js As"));
+        assert!(value("Ks Kh 3c 3d 2s") > value("Kc Kd 2h 2s As"));
+        // The three of a kind decides a full house
+        assert!(value("3s 3h 3c 2d 2s") > value("2c 2d 2h As Ac"));
+        // The wheel is the lowest straight
+        assert!(value("2s 3h 4c 5d 6s") > value("As 2h 3c 4d 5s"));
+        assert!(value("As Kh Qc Jd Ts") > value("9s Kh Qc Jd Ts"));
+        // High card compares every card
+        assert!(value("As Kh Qc Jd 9s") > value("As Kh Qc Jd 8s"));
+    }
+
+    #[test]
+    fn hand_wrong_size() {
+        assert_eq!(Err(PokerError::WrongHandSize(4)), check_hand(&hand("As Ks Qs Js")));
+        assert_eq!(Err(PokerError::WrongHandSize(0)), check_hand(&[]));
+    }
+
+    #[test]
+    fn hand_best_of_seven() {
+        let best = best_hand(&hand("Ah Kd Qh Jh 3h 2h 2c")).unwrap();
+
+        assert_eq!(HandCategory::Flush, best.value.category());
+        assert_eq!(
+            Err(PokerError::WrongCardCount { count: 4, min: 5, max: 7 }),
+            best_hand(&hand("Ah Kd Qh Jh"))
+        );
+    }
+
+    #[test]
+    fn hand_with_wilds() {
+        let rules = Rules {
+            jokers: 1,
+            wild_rank: None,
+            ..Rules::default()
+        };
+
+        assert_eq!(
+            Ok(HandCategory::WildRoyalFlush),
+            check_hand_with(&hand("Jk Kh Qh Jh Th"), &rules).map(|v| v.category())
+        );
+        assert_eq!(
+            Ok(HandCategory::RoyalFlush),
+            check_hand_with(&hand("Ah Kh Qh Jh Th"), &rules).map(|v| v.category())
+        );
+    }
+
+    #[test]
+    fn hand_lowball() {
+        let wheel = check_low_hand(&hand("As 2h 3d 4c 5s"), Lowball::AceToFive).unwrap();
+        let seven = check_low_hand(&hand("7s 2h 3d 4c 5s"), Lowball::AceToFive).unwrap();
+
+        assert!(wheel > seven);
+        assert_eq!(
+            Err(PokerError::WrongHandSize(6)),
+            check_low_hand(&hand("As 2h 3d 4c 5s 6s"), Lowball::DeuceToSeven)
+        );
     }
 
     #[test]
     fn hand_royal_flush() {
-        let card1 = Card::new(40); // A of clubs
-        let card2 = Card::new(49); // 10 of clubs
-        let card3 = Card::new(50); // J of clubs
-        let card4 = Card::new(51); // Q of clubs
-        let card5 = Card::new(52); // K of clubs
-        let hand = vec![card5, card4, card2, card1, card3];
-
-        assert_eq!(40, check_hand(&hand));
-
-        let card1 = Card::new(27); // A of diamonds
-        let card2 = Card::new(49);
-        let card3 = Card::new(50);
-        let card4 = Card::new(51);
-        let card5 = Card::new(52);
-        let hand2 = vec![card5, card4, card2, card1, card3];
-
-        assert_ne!(40, check_hand(&hand2));
+        assert_eq!(HandCategory::RoyalFlush, category("Kc Qc Tc Ac Jc"));
+
+        // Ace of diamonds breaks the flush
+        assert_ne!(HandCategory::RoyalFlush, category("Kc Qc Tc Ad Jc"));
     }
 }
@@ -0,0 +1,303 @@
+use crate::game::deck::Deck;
+use crate::game::eval;
+use crate::game::hand::{HandCategory, HandValue};
+use crate::{Card, Rank, Suit, MAX_DECKS};
+use std::fmt;
+
+// Deck and hand rankings the game is played with
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Variant {
+    #[default]
+    Standard,
+    // 36 card deck without the 2s to 5s. A-6-7-8-9 is a straight and
+    // a flush beats a full house.
+    ShortDeck,
+}
+
+// Rules the draw game is played with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rules {
+    pub variant: Variant,
+    // Decks shuffled together in the shoe, from 1 to MAX_DECKS
+    pub decks: u8,
+    // Jokers added to the deck, they are always wild
+    pub jokers: u8,
+    // Every card of this rank is wild
+    pub wild_rank: Option<Rank>,
+}
+
+impl Rules {
+    // Ranks of the natural cards in the deck
+    pub fn ranks(&self) -> &'static [Rank] {
+        match self.variant {
+            Variant::Standard => &Rank::ALL,
+            Variant::ShortDeck => &Rank::ALL[4..],
+        }
+    }
+
+    pub fn new_deck(&self) -> Deck {
+        let cards = (0..self.decks.clamp(1, MAX_DECKS))
+            .flat_map(|deck| {
+                Suit::iter().flat_map(move |suit| {
+                    self.ranks().iter().map(move |rank| Card::new(*rank, suit).in_deck(deck))
+                })
+            })
+            .chain((0..self.jokers.min(4)).map(Card::joker))
+            .collect();
+
+        Deck::from_cards(cards)
+    }
+
+    pub fn is_wild(&self, card: &Card) -> bool {
+        card.joker || Some(card.rank) == self.wild_rank
+    }
+
+    pub fn has_wilds(&self) -> bool {
+        self.jokers > 0 || self.wild_rank.is_some()
+    }
+
+    pub fn evaluate(&self, cards: &[Card; 5]) -> HandValue {
+        let evaluate: fn(&[Card; 5]) -> HandValue = match self.variant {
+            Variant::Standard => eval::evaluate,
+            Variant::ShortDeck => eval::evaluate_short_deck,
+        };
+
+        if !self.has_wilds() {
+            return evaluate(cards);
+        }
+
+        eval::evaluate_wild(cards, |c| self.is_wild(c), self.ranks(), evaluate)
+    }
+
+    // Whether a hand of the category can come up with these rules
+    pub fn possible(&self, category: HandCategory) -> bool {
+        match category {
+            HandCategory::FiveOfAKind => self.has_wilds() || self.decks > 1,
+            HandCategory::WildRoyalFlush => self.has_wilds(),
+            _ => true,
+        }
+    }
+
+    // Hand categories from the weakest to the strongest
+    pub fn categories(&self) -> Vec<HandCategory> {
+        let mut categories = HandCategory::ALL.to_vec();
+
+        if self.variant == Variant::ShortDeck {
+            let flush = HandCategory::Flush as usize;
+            categories.swap(flush, flush + 1);
+        }
+
+        categories
+    }
+
+    // Number of 5 card hands of each category that can be dealt from a
+    // new deck, indexed by category. Every hand is evaluated so it can
+    // take a few seconds with many wild cards.
+    pub fn deal_counts(&self) -> [u64; HandCategory::ALL.len()] {
+        // The copies of a card from each deck, and the jokers, are all
+        // the same to the evaluator. Each kind of card is only counted
+        // once along with the number of ways to pick the copies.
+        let mut kinds: Vec<(Card, u64)> = vec![];
+        for card in self.new_deck().remaining() {
+            let card = if card.joker { Card::joker(0) } else { card.in_deck(0) };
+            match kinds.iter_mut().find(|(c, _)| *c == card) {
+                Some(kind) => kind.1 += 1,
+                None => kinds.push((card, 1)),
+            }
+        }
+
+        let mut counts = [0; HandCategory::ALL.len()];
+        for_each_deal(&kinds, 0, &mut vec![], 1, &mut |cards, ways| {
+            counts[self.evaluate(cards).category() as usize] += ways;
+        });
+
+        counts
+    }
+}
+
+impl Default for Rules {
+    fn default() -> Rules {
+        Rules {
+            variant: Variant::Standard,
+            decks: 1,
+            jokers: 0,
+            wild_rank: None,
+        }
+    }
+}
+
+// Calls f with every 5 card hand made from the kinds of cards and the
+// number of ways it can be dealt
+fn for_each_deal<F: FnMut(&[Card; 5], u64)>(
+    kinds: &[(Card, u64)],
+    from: usize,
+    hand: &mut Vec<Card>,
+    ways: u64,
+    f: &mut F,
+) {
+    if let Ok(cards) = hand[..].try_into() {
+        f(cards, ways);
+        return;
+    }
+
+    let len = hand.len();
+    for (i, (card, copies)) in kinds.iter().enumerate().skip(from) {
+        // Ways to pick k of the copies
+        let mut choose = 1;
+        for k in 1..=(*copies).min(5 - len as u64) {
+            choose = choose * (copies - k + 1) / k;
+            hand.push(*card);
+            for_each_deal(kinds, i + 1, hand, ways * choose, f);
+        }
+        hand.truncate(len);
+    }
+}
+
+impl fmt::Display for Rules {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut wilds = vec![];
+
+        match self.jokers {
+            0 => {}
+            1 => wilds.push("1 joker".to_string()),
+            n => wilds.push(format!("{} jokers", n)),
+        }
+
+        if let Some(rank) = self.wild_rank {
+            wilds.push(match rank {
+                Rank::Two => "deuces".to_string(),
+                Rank::Six => "sixes".to_string(),
+                _ => format!("{}s", rank.name().to_lowercase()),
+            });
+        }
+
+        let wilds = match wilds.is_empty() {
+            true => "no wild cards".to_string(),
+            false => format!("{} wild", wilds.join(" and ")),
+        };
+        let mut rules = vec![];
+        if self.variant == Variant::ShortDeck {
+            rules.push("short deck (6+)".to_string());
+        }
+        if self.decks > 1 {
+            rules.push(format!("{} decks", self.decks));
+        }
+        rules.push(wilds);
+        let rules = rules.join(", ");
+
+        let mut chars = rules.chars();
+        let first = chars.next().map(|c| c.to_ascii_uppercase()).unwrap_or_default();
+
+        write!(f, "{}{}", first, chars.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_hand;
+    use crate::Suit;
+
+    fn hand(s: &str) -> [Card; 5] {
+        parse_hand(s).unwrap().try_into().unwrap()
+    }
+
+    #[test]
+    fn natural_rules() {
+        let rules = Rules::default();
+
+        assert!(!rules.has_wilds());
+        assert!(!rules.possible(HandCategory::FiveOfAKind));
+        assert_eq!(52, rules.new_deck().len());
+        assert_eq!(HandCategory::Pair, rules.evaluate(&hand("2s 2h 5d 7c 9c")).category());
+        assert_eq!("No wild cards", rules.to_string());
+    }
+
+    #[test]
+    fn deuces_wild() {
+        let rules = Rules {
+            wild_rank: Some(Rank::Two),
+            ..Rules::default()
+        };
+
+        assert!(rules.is_wild(&Card::new(Rank::Two, Suit::Clubs)));
+        assert!(!rules.is_wild(&Card::new(Rank::Three, Suit::Clubs)));
+        assert!(rules.possible(HandCategory::WildRoyalFlush));
+        assert_eq!(HandCategory::ThreeOfAKind, rules.evaluate(&hand("2s 2h 5d 7c Kc")).category());
+        assert_eq!("Deuces wild", rules.to_string());
+    }
+
+    #[test]
+    fn jokers_wild() {
+        let rules = Rules {
+            jokers: 2,
+            wild_rank: Some(Rank::Nine),
+            ..Rules::default()
+        };
+
+        assert_eq!(54, rules.new_deck().len());
+        assert_eq!(HandCategory::FourOfAKind, rules.evaluate(&hand("Jk 2h 2d 7c 9c")).category());
+        assert_eq!("2 jokers and nines wild", rules.to_string());
+    }
+
+    #[test]
+    fn short_deck() {
+        let rules = Rules {
+            variant: Variant::ShortDeck,
+            ..Rules::default()
+        };
+        let deck = rules.new_deck();
+
+        assert_eq!(36, deck.len());
+        assert!(deck.remaining().iter().all(|c| c.rank >= Rank::Six));
+        assert_eq!(HandCategory::Straight, rules.evaluate(&hand("As 6h 7d 8c 9c")).category());
+        assert!(rules.evaluate(&hand("6s 8s 9s Js Ks")) > rules.evaluate(&hand("As Ah Ad Kc Ks")));
+        assert_eq!(HandCategory::FullHouse, rules.categories()[5]);
+        assert_eq!(HandCategory::Flush, rules.categories()[6]);
+        assert_eq!("Short deck (6+), no wild cards", rules.to_string());
+
+        let rules = Rules { jokers: 1, ..rules };
+        assert_eq!(37, rules.new_deck().len());
+        assert_eq!(HandCategory::Straight, rules.evaluate(&hand("Jk 6h 7d 8c 9c")).category());
+        assert_eq!("Short deck (6+), 1 joker wild", rules.to_string());
+    }
+
+    #[test]
+    fn shoe() {
+        let rules = Rules {
+            decks: 2,
+            ..Rules::default()
+        };
+        let mut cards = rules.new_deck().remaining().to_vec();
+        cards.sort();
+        cards.dedup();
+
+        assert_eq!(104, cards.len());
+        assert!(rules.possible(HandCategory::FiveOfAKind));
+        assert_eq!(HandCategory::Flush, rules.evaluate(&hand("Ks Ks 8s 5s 3s")).category());
+        assert_eq!("2 decks, no wild cards", rules.to_string());
+    }
+
+    #[test]
+    fn deal_counts() {
+        let rules = Rules {
+            variant: Variant::ShortDeck,
+            ..Rules::default()
+        };
+        let counts = rules.deal_counts();
+
+        assert_eq!(376_992, counts.iter().sum::<u64>());
+        assert_eq!(
+            [122_400, 193_536, 36_288, 16_128, 6_120, 480, 1_728, 288, 20, 0, 0, 4],
+            counts
+        );
+
+        // Two short decks, five of a kind is 5 of the 8 cards of a rank
+        let counts = Rules { decks: 2, ..rules }.deal_counts();
+        assert_eq!(13_991_544, counts.iter().sum::<u64>());
+        assert_eq!(9 * 56, counts[HandCategory::FiveOfAKind as usize]);
+
+        let counts = Rules { jokers: 2, ..rules }.deal_counts();
+        assert_eq!(501_942, counts.iter().sum::<u64>());
+    }
+}
@@ -0,0 +1,160 @@
+use crate::game::error::PokerError;
+use crate::game::hand::HandCategory;
+use crate::game::paytable::PayTable;
+use crate::game::poker;
+use crate::game::rules::Rules;
+use crate::game::strategy::Strategy;
+use rand::Rng;
+
+// Totals of a run of hands, every hand being a bet of one point
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Report {
+    pub hands: u64,
+    // Points won over all the hands
+    pub total: i64,
+    // Sum of the squared points of each hand, for the variance
+    pub total_squares: i64,
+    // Hands ending in each category, indexed by category
+    pub counts: [u64; HandCategory::ALL.len()],
+    // Hands that won any points
+    pub wins: u64,
+}
+
+impl Report {
+    pub fn record(&mut self, category: HandCategory, points: i32) {
+        self.hands += 1;
+        self.total += points as i64;
+        self.total_squares += points as i64 * points as i64;
+        self.counts[category as usize] += 1;
+        if points > 0 {
+            self.wins += 1;
+        }
+    }
+
+    // Average points won per hand
+    pub fn mean(&self) -> f64 {
+        if self.hands == 0 {
+            return 0.0;
+        }
+
+        self.total as f64 / self.hands as f64
+    }
+
+    // Return to player, the share of the bets paid back
+    pub fn rtp(&self) -> f64 {
+        self.mean()
+    }
+
+    // Variance of the points won in one hand
+    pub fn variance(&self) -> f64 {
+        if self.hands == 0 {
+            return 0.0;
+        }
+
+        let mean = self.mean();
+        self.total_squares as f64 / self.hands as f64 - mean * mean
+    }
+
+    // Share of the hands ending in the category
+    pub fn hit_frequency(&self, category: HandCategory) -> f64 {
+        if self.hands == 0 {
+            return 0.0;
+        }
+
+        self.counts[category as usize] as f64 / self.hands as f64
+    }
+
+    // Share of the hands that won anything
+    pub fn win_frequency(&self) -> f64 {
+        if self.hands == 0 {
+            return 0.0;
+        }
+
+        self.wins as f64 / self.hands as f64
+    }
+}
+
+// Plays the given number of hands of the draw game with no one at the
+// keyboard, the strategy picks the cards to change
+pub fn simulate<R, S>(
+    hands: u64,
+    rules: &Rules,
+    pay_table: &PayTable,
+    rng: &mut R,
+    strategy: &mut S,
+) -> Result<Report, PokerError>
+where
+    R: Rng + ?Sized,
+    S: Strategy + ?Sized,
+{
+    let mut deck = rules.new_deck();
+    let mut report = Report::default();
+
+    for _ in 0..hands {
+        let mut hand = poker::deal(&mut deck, rng)?;
+
+        let to_change = strategy.choose(&hand, rules);
+        poker::change_cards(&mut deck, &mut hand, &to_change)?;
+        let value = poker::check_hand_with(&hand, rules)?;
+
+        report.record(value.category(), pay_table.points(value));
+        poker::reset_deck(&mut deck);
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::strategy::{KeepPairs, Stand};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn report() {
+        let mut report = Report::default();
+        assert_eq!(0.0, report.rtp());
+
+        report.record(HandCategory::HighCard, 0);
+        report.record(HandCategory::Pair, 1);
+        report.record(HandCategory::Flush, 6);
+        report.record(HandCategory::HighCard, 0);
+
+        assert_eq!(4, report.hands);
+        assert_eq!(7, report.total);
+        assert_eq!(1.75, report.rtp());
+        assert_eq!(37.0 / 4.0 - 1.75 * 1.75, report.variance());
+        assert_eq!(0.5, report.hit_frequency(HandCategory::HighCard));
+        assert_eq!(0.5, report.win_frequency());
+    }
+
+    #[test]
+    fn standing_pat() {
+        let rules = Rules::default();
+        let table = PayTable::standard();
+        let report = simulate(2000, &rules, &table, &mut StdRng::seed_from_u64(7), &mut Stand).unwrap();
+
+        assert_eq!(2000, report.hands);
+        assert_eq!(2000, report.counts.iter().sum::<u64>());
+
+        // About half the hands dealt are a pair or better
+        let high_cards = report.hit_frequency(HandCategory::HighCard);
+        assert!(high_cards > 0.45 && high_cards < 0.55);
+
+        // The same seed plays the same hands
+        let again = simulate(2000, &rules, &table, &mut StdRng::seed_from_u64(7), &mut Stand).unwrap();
+        assert_eq!(report, again);
+    }
+
+    #[test]
+    fn changing_cards() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let report =
+            simulate(500, &Rules::default(), &PayTable::standard(), &mut rng, &mut KeepPairs).unwrap();
+
+        // Keeping pairs never ends with less than what was dealt
+        assert_eq!(500, report.hands);
+        assert!(report.hit_frequency(HandCategory::HighCard) < 0.5);
+    }
+}
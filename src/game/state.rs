@@ -0,0 +1,554 @@
+use crate::game::deck::Deck;
+use crate::game::error::PokerError;
+use crate::game::hand::HandValue;
+use crate::game::paytable::PayTable;
+use crate::game::poker::{self, MAX_CHANGES};
+use crate::game::rules::Rules;
+use crate::Card;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+// Cards in a hand, and so positions the cursor can be on
+pub const HAND_SIZE: usize = 5;
+
+// What the player can do
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Action {
+    // Moves the cursor by an offset, wrapping around the hand
+    MoveCursor(isize),
+    // Selects or deselects the card under the cursor
+    ToggleCard,
+    // Replaces the whole selection, for strategies playing on their own
+    Select(Vec<usize>),
+    Deal,
+    Draw,
+    Quit,
+}
+
+// Where the game is in a hand
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Phase {
+    // Nothing dealt yet
+    AwaitingDeal,
+    // A hand is in play and the player picks the cards to change
+    ChoosingDiscards,
+    // The cards were drawn, the next deal starts a new hand
+    ShowingResult(HandResult),
+}
+
+// How a hand ended
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HandResult {
+    // Positions of the cards changed, in the order they were selected
+    pub to_change: Vec<usize>,
+    // Cards thrown away, in the same order
+    pub discarded: Vec<Card>,
+    // Cards drawn in their place, in the same order
+    pub replacements: Vec<Card>,
+    pub value: HandValue,
+    pub points: i32,
+}
+
+// What changed after an action, for whoever shows the game
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GameEvent {
+    CursorMoved(usize),
+    SelectionChanged(Vec<usize>),
+    Dealt(Vec<Card>),
+    Drawn(HandResult),
+    Quit,
+}
+
+// Everything needed to pick a session back up where it was left
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Snapshot {
+    pub rules: Rules,
+    pub pay_table: PayTable,
+    pub seed: u64,
+    // Hands dealt so far, the next one is shuffled from the seed and
+    // this number
+    pub deals: u32,
+    pub score: i32,
+    pub hands_played: u32,
+    // The hand in play, empty between hands
+    pub hand: Vec<Card>,
+    // Cards left to draw in the order they come
+    pub draw_pile: Vec<Card>,
+    pub to_change: Vec<usize>,
+    pub cursor: usize,
+}
+
+// Everything about a game session apart from how it's shown. Actions
+// don't change a state, they make the next one, so an action that
+// fails leaves the game as it was.
+#[derive(Clone, Debug)]
+pub struct GameState {
+    rules: Rules,
+    pay_table: PayTable,
+    // Every shuffle comes from it so a session can be replayed
+    seed: u64,
+    deals: u32,
+    deck: Deck,
+    hand: Vec<Card>,
+    // Positions of the cards to change, in the order they were selected
+    to_change: Vec<usize>,
+    cursor: usize,
+    score: i32,
+    hands_played: u32,
+    phase: Phase,
+}
+
+impl GameState {
+    pub fn new(rules: Rules, pay_table: PayTable, seed: u64) -> GameState {
+        GameState {
+            deck: rules.new_deck(),
+            rules,
+            pay_table,
+            seed,
+            deals: 0,
+            hand: vec![],
+            to_change: vec![],
+            cursor: 0,
+            score: 0,
+            hands_played: 0,
+            phase: Phase::AwaitingDeal,
+        }
+    }
+
+    // Picks a session back up. The cards have to be the deck of the
+    // rules, with the hand as the only ones dealt.
+    pub fn resume(snapshot: Snapshot) -> Result<GameState, PokerError> {
+        let mut state = GameState::new(snapshot.rules, snapshot.pay_table, snapshot.seed);
+
+        let mut cards: Vec<u16> =
+            snapshot.hand.iter().chain(&snapshot.draw_pile).map(|c| c.value()).collect();
+        let mut deck_cards: Vec<u16> = state.deck.remaining().iter().map(|c| c.value()).collect();
+        cards.sort_unstable();
+        deck_cards.sort_unstable();
+        if cards != deck_cards {
+            return Err(PokerError::WrongDeck);
+        }
+
+        if !snapshot.hand.is_empty() {
+            if snapshot.hand.len() != HAND_SIZE {
+                return Err(PokerError::WrongHandSize(snapshot.hand.len()));
+            }
+            check_selection(&snapshot.to_change, HAND_SIZE)?;
+            state.to_change = snapshot.to_change;
+            state.phase = Phase::ChoosingDiscards;
+        }
+
+        state.deals = snapshot.deals;
+        state.deck = Deck::with_dealt(snapshot.draw_pile, snapshot.hand.clone());
+        state.hand = snapshot.hand;
+        state.cursor = snapshot.cursor % HAND_SIZE;
+        state.score = snapshot.score;
+        state.hands_played = snapshot.hands_played;
+
+        Ok(state)
+    }
+
+    // What to keep to resume the session later. A hand's result isn't
+    // kept, the session picks up before the next deal.
+    pub fn snapshot(&self) -> Snapshot {
+        let in_play = self.in_play();
+
+        Snapshot {
+            rules: self.rules,
+            pay_table: self.pay_table.clone(),
+            seed: self.seed,
+            deals: self.deals,
+            score: self.score,
+            hands_played: self.hands_played,
+            hand: if in_play { self.hand.clone() } else { vec![] },
+            draw_pile: self.deck.remaining().to_vec(),
+            to_change: if in_play { self.to_change.clone() } else { vec![] },
+            cursor: self.cursor,
+        }
+    }
+
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    pub fn pay_table(&self) -> &PayTable {
+        &self.pay_table
+    }
+
+    pub fn hand(&self) -> &[Card] {
+        &self.hand
+    }
+
+    pub fn to_change(&self) -> &[usize] {
+        &self.to_change
+    }
+
+    // Cards that can still be drawn
+    pub fn remaining(&self) -> &[Card] {
+        self.deck.remaining()
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn score(&self) -> i32 {
+        self.score
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    // Hands dealt so far
+    pub fn deals(&self) -> u32 {
+        self.deals
+    }
+
+    // Hands drawn and scored so far
+    pub fn hands_played(&self) -> u32 {
+        self.hands_played
+    }
+
+    pub fn phase(&self) -> &Phase {
+        &self.phase
+    }
+
+    pub fn in_play(&self) -> bool {
+        self.phase == Phase::ChoosingDiscards
+    }
+
+    // Result of the last hand while it's shown
+    pub fn result(&self) -> Option<&HandResult> {
+        match &self.phase {
+            Phase::ShowingResult(result) => Some(result),
+            _ => None,
+        }
+    }
+
+    // The state after the action and what happened on the way
+    pub fn update(&self, action: Action) -> Result<(GameState, Vec<GameEvent>), PokerError> {
+        let mut next = self.clone();
+        let events = next.apply(action)?;
+
+        Ok((next, events))
+    }
+
+    fn apply(&mut self, action: Action) -> Result<Vec<GameEvent>, PokerError> {
+        match action {
+            Action::MoveCursor(offset) => {
+                let size = HAND_SIZE as isize;
+                self.cursor = (self.cursor as isize + offset).rem_euclid(size) as usize;
+
+                Ok(vec![GameEvent::CursorMoved(self.cursor)])
+            }
+            Action::ToggleCard => {
+                self.check_in_play()?;
+
+                let selection = self.cursor;
+                if self.to_change.contains(&selection) {
+                    self.to_change.retain(|i| *i != selection);
+                } else {
+                    // The latest choice makes way for the new one
+                    if self.to_change.len() == MAX_CHANGES {
+                        self.to_change.pop();
+                    }
+                    self.to_change.push(selection);
+                }
+
+                Ok(vec![GameEvent::SelectionChanged(self.to_change.clone())])
+            }
+            Action::Select(to_change) => {
+                self.check_in_play()?;
+                check_selection(&to_change, self.hand.len())?;
+                self.to_change = to_change;
+
+                Ok(vec![GameEvent::SelectionChanged(self.to_change.clone())])
+            }
+            Action::Deal => {
+                if self.in_play() {
+                    return Err(PokerError::HandInPlay);
+                }
+
+                // A whole deck in the same order every time, so the
+                // cards don't depend on how the last hand was played
+                self.deck = self.rules.new_deck();
+                let mut rng = StdRng::seed_from_u64(hand_seed(self.seed, self.deals));
+                self.hand = poker::deal(&mut self.deck, &mut rng)?;
+                self.deals += 1;
+                self.to_change.clear();
+                self.phase = Phase::ChoosingDiscards;
+
+                Ok(vec![GameEvent::Dealt(self.hand.clone())])
+            }
+            Action::Draw => {
+                self.check_in_play()?;
+
+                let discarded = poker::change_cards(&mut self.deck, &mut self.hand, &self.to_change)?;
+                let replacements = self.to_change.iter().map(|i| self.hand[*i]).collect();
+                let value = poker::check_hand_with(&self.hand, &self.rules)?;
+                let result = HandResult {
+                    to_change: self.to_change.clone(),
+                    discarded,
+                    replacements,
+                    value,
+                    points: self.pay_table.points(value),
+                };
+
+                self.score += result.points;
+                self.hands_played += 1;
+                self.to_change.clear();
+                poker::reset_deck(&mut self.deck);
+                self.phase = Phase::ShowingResult(result.clone());
+
+                Ok(vec![GameEvent::Drawn(result)])
+            }
+            Action::Quit => Ok(vec![GameEvent::Quit]),
+        }
+    }
+
+    fn check_in_play(&self) -> Result<(), PokerError> {
+        match self.in_play() {
+            true => Ok(()),
+            false => Err(PokerError::NoHandInPlay),
+        }
+    }
+}
+
+// Each hand is shuffled with a generator of its own, seeded from the
+// session and the number of hands dealt before it. A saved session
+// only needs that number to go on with the same cards.
+fn hand_seed(seed: u64, deals: u32) -> u64 {
+    seed ^ (deals as u64 + 1).wrapping_mul(0x9e37_79b9_7f4a_7c15)
+}
+
+// Same checks as changing the cards, before anything is changed
+fn check_selection(to_change: &[usize], hand_size: usize) -> Result<(), PokerError> {
+    if to_change.len() > MAX_CHANGES {
+        return Err(PokerError::TooManyChanges {
+            requested: to_change.len(),
+            max: MAX_CHANGES,
+        });
+    }
+
+    for (n, i) in to_change.iter().enumerate() {
+        if *i >= hand_size {
+            return Err(PokerError::InvalidIndex(*i));
+        }
+        if to_change[..n].contains(i) {
+            return Err(PokerError::DuplicateIndex(*i));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::hand::HandCategory;
+    use crate::parse_hand;
+
+    fn new_game() -> GameState {
+        GameState::new(Rules::default(), PayTable::standard(), 42)
+    }
+
+    // Applies the actions one after the other, they all have to work
+    fn play(state: &GameState, actions: &[Action]) -> GameState {
+        actions.iter().fold(state.clone(), |state, action| {
+            state.update(action.clone()).unwrap().0
+        })
+    }
+
+    #[test]
+    fn phases() {
+        let state = new_game();
+        assert_eq!(&Phase::AwaitingDeal, state.phase());
+
+        let (state, events) = state.update(Action::Deal).unwrap();
+        assert_eq!(&Phase::ChoosingDiscards, state.phase());
+        assert_eq!(vec![GameEvent::Dealt(state.hand().to_vec())], events);
+        assert_eq!(HAND_SIZE, state.hand().len());
+        assert_eq!(47, state.remaining().len());
+
+        let (state, events) = state.update(Action::Draw).unwrap();
+        let result = state.result().unwrap().clone();
+        assert_eq!(vec![GameEvent::Drawn(result.clone())], events);
+        assert_eq!(1, state.hands_played());
+        assert_eq!(result.points, state.score());
+        assert_eq!(52, state.remaining().len());
+
+        let state = play(&state, &[Action::Deal]);
+        assert!(state.in_play());
+        assert_eq!(None, state.result());
+    }
+
+    #[test]
+    fn cursor() {
+        let state = play(&new_game(), &[Action::MoveCursor(-1)]);
+        assert_eq!(4, state.cursor());
+
+        let (state, events) = state.update(Action::MoveCursor(2)).unwrap();
+        assert_eq!(1, state.cursor());
+        assert_eq!(vec![GameEvent::CursorMoved(1)], events);
+    }
+
+    #[test]
+    fn toggling_cards() {
+        let state = play(
+            &new_game(),
+            &[Action::Deal, Action::ToggleCard, Action::MoveCursor(2), Action::ToggleCard],
+        );
+        assert_eq!(&[0, 2], state.to_change());
+
+        let (state, events) = state.update(Action::ToggleCard).unwrap();
+        assert_eq!(vec![GameEvent::SelectionChanged(vec![0])], events);
+
+        // The latest card makes way when too many are selected
+        let state = play(&state, &[
+            Action::ToggleCard,
+            Action::MoveCursor(1),
+            Action::ToggleCard,
+            Action::MoveCursor(1),
+            Action::ToggleCard,
+        ]);
+        assert_eq!(&[0, 2, 4], state.to_change());
+    }
+
+    #[test]
+    fn drawing() {
+        // Seed 42 deals 6h Jc 5c Jd 7s, the jacks are kept
+        let state = play(&new_game(), &[Action::Deal, Action::Select(vec![0, 2, 4])]);
+        let hand = state.hand().to_vec();
+        assert_eq!(parse_hand("6h Jc 5c Jd 7s").unwrap(), hand);
+        let (state, _) = state.update(Action::Draw).unwrap();
+        let result = state.result().unwrap();
+
+        assert_eq!(vec![0, 2, 4], result.to_change);
+        assert_eq!(vec![hand[0], hand[2], hand[4]], result.discarded);
+        assert_eq!(result.replacements, vec![state.hand()[0], state.hand()[2], state.hand()[4]]);
+        assert_eq!((hand[1], hand[3]), (state.hand()[1], state.hand()[3]));
+        assert!(result.value.category() >= HandCategory::Pair);
+        assert_eq!(state.pay_table().points(result.value), result.points);
+        assert!(state.to_change().is_empty());
+    }
+
+    #[test]
+    fn wrong_phase() {
+        let state = new_game();
+        assert_eq!(Some(PokerError::NoHandInPlay), state.update(Action::ToggleCard).err());
+        assert_eq!(Some(PokerError::NoHandInPlay), state.update(Action::Draw).err());
+
+        let state = play(&state, &[Action::Deal]);
+        assert_eq!(Some(PokerError::HandInPlay), state.update(Action::Deal).err());
+    }
+
+    #[test]
+    fn invalid_selections() {
+        let state = play(&new_game(), &[Action::Deal, Action::Select(vec![1])]);
+
+        assert_eq!(
+            Some(PokerError::TooManyChanges { requested: 4, max: 3 }),
+            state.update(Action::Select(vec![0, 1, 2, 3])).err()
+        );
+        assert_eq!(Some(PokerError::InvalidIndex(5)), state.update(Action::Select(vec![5])).err());
+        assert_eq!(Some(PokerError::DuplicateIndex(2)), state.update(Action::Select(vec![2, 2])).err());
+
+        // Failed actions leave the state as it was
+        assert_eq!(&[1], state.to_change());
+    }
+
+    #[test]
+    fn replays() {
+        let actions = [Action::Deal, Action::Select(vec![0, 1]), Action::Draw, Action::Deal];
+
+        let first = play(&new_game(), &actions);
+        let again = play(&new_game(), &actions);
+        let other = play(&GameState::new(Rules::default(), PayTable::standard(), 43), &actions);
+
+        assert_eq!(first.hand(), again.hand());
+        assert_eq!(first.score(), again.score());
+        assert_ne!(first.hand(), other.hand());
+    }
+
+    #[test]
+    fn same_deals_whatever_the_play() {
+        let played = |seed: u64, to_change: Vec<usize>| {
+            let state = GameState::new(Rules::default(), PayTable::standard(), seed);
+            let actions = [Action::Deal, Action::Select(to_change), Action::Draw, Action::Deal];
+            play(&state, &actions).hand().to_vec()
+        };
+
+        // The daily seed of 2024-03-09 and a few more
+        for seed in (0..20).chain([17010361685977357416]) {
+            let pat = played(seed, vec![]);
+            assert_eq!(pat, played(seed, vec![0, 1, 2]));
+            assert_eq!(pat, played(seed, vec![2, 1, 0]));
+            assert_eq!(pat, played(seed, vec![4]));
+        }
+    }
+
+    #[test]
+    fn resuming() {
+        let actions = [
+            Action::Deal,
+            Action::Select(vec![0, 4]),
+            Action::Draw,
+            Action::Deal,
+            Action::MoveCursor(2),
+            Action::ToggleCard,
+        ];
+        let state = play(&new_game(), &actions);
+        let snapshot = state.snapshot();
+        assert_eq!(2, snapshot.deals);
+        assert_eq!(state.hand(), &snapshot.hand[..]);
+        assert_eq!(vec![2], snapshot.to_change);
+
+        let resumed = GameState::resume(snapshot.clone()).unwrap();
+        assert_eq!(snapshot, resumed.snapshot());
+        assert!(resumed.in_play());
+
+        // The session goes on with the same cards as if it never stopped
+        let rest = [Action::Draw, Action::Deal, Action::Draw, Action::Deal];
+        let uninterrupted = play(&state, &rest);
+        let resumed = play(&resumed, &rest);
+        assert_eq!(uninterrupted.snapshot(), resumed.snapshot());
+
+        // Between hands only the score and the deck are kept
+        let between = play(&state, &[Action::Draw]).snapshot();
+        assert!(between.hand.is_empty());
+        let resumed = GameState::resume(between.clone()).unwrap();
+        assert_eq!(&Phase::AwaitingDeal, resumed.phase());
+        assert_eq!(between.score, resumed.score());
+    }
+
+    #[test]
+    fn invalid_snapshots() {
+        let snapshot = play(&new_game(), &[Action::Deal]).snapshot();
+
+        let mut missing = snapshot.clone();
+        missing.draw_pile.pop();
+        assert_eq!(Some(PokerError::WrongDeck), GameState::resume(missing).err());
+
+        let mut twice = snapshot.clone();
+        twice.draw_pile[0] = twice.hand[0];
+        assert_eq!(Some(PokerError::WrongDeck), GameState::resume(twice).err());
+
+        let mut short = snapshot.clone();
+        let card = short.hand.pop().unwrap();
+        short.draw_pile.push(card);
+        assert_eq!(Some(PokerError::WrongHandSize(4)), GameState::resume(short).err());
+
+        let mut selection = snapshot;
+        selection.to_change = vec![7];
+        assert_eq!(Some(PokerError::InvalidIndex(7)), GameState::resume(selection).err());
+    }
+
+    #[test]
+    fn quitting() {
+        let (state, events) = new_game().update(Action::Quit).unwrap();
+
+        assert_eq!(vec![GameEvent::Quit], events);
+        assert_eq!(&Phase::AwaitingDeal, state.phase());
+    }
+}
@@ -0,0 +1,308 @@
+use crate::game::advisor;
+use crate::game::hand::HandCategory;
+use crate::game::paytable::PayTable;
+use crate::game::poker::MAX_CHANGES;
+use crate::game::rules::Rules;
+use crate::{Card, Rank};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+// Something that picks which cards of a hand to change, by index
+pub trait Strategy {
+    fn name(&self) -> &str;
+
+    fn choose(&mut self, hand: &[Card], rules: &Rules) -> Vec<usize>;
+}
+
+// Names the built-in strategies are known by
+pub const NAMES: [&str; 5] = ["stand", "random", "keep-pairs", "chart", "optimal"];
+
+// Built-in strategy by name. The seed is only used by the random one.
+pub fn by_name(name: &str, pay_table: &PayTable, seed: u64) -> Option<Box<dyn Strategy + Send>> {
+    match name {
+        "stand" => Some(Box::new(Stand)),
+        "random" => Some(Box::new(RandomChoice::new(StdRng::seed_from_u64(seed)))),
+        "keep-pairs" => Some(Box::new(KeepPairs)),
+        "chart" => Some(Box::new(Chart)),
+        "optimal" => Some(Box::new(Optimal::new(pay_table.clone()))),
+        _ => None,
+    }
+}
+
+// Never changes a card
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Stand;
+
+impl Strategy for Stand {
+    fn name(&self) -> &str {
+        "stand"
+    }
+
+    fn choose(&mut self, _hand: &[Card], _rules: &Rules) -> Vec<usize> {
+        vec![]
+    }
+}
+
+// Changes a random number of random cards
+#[derive(Clone, Debug)]
+pub struct RandomChoice<R: Rng> {
+    rng: R,
+}
+
+impl<R: Rng> RandomChoice<R> {
+    pub fn new(rng: R) -> RandomChoice<R> {
+        RandomChoice { rng }
+    }
+}
+
+impl<R: Rng> Strategy for RandomChoice<R> {
+    fn name(&self) -> &str {
+        "random"
+    }
+
+    fn choose(&mut self, hand: &[Card], _rules: &Rules) -> Vec<usize> {
+        let n = self.rng.gen_range(0..=MAX_CHANGES.min(hand.len()));
+        let mut to_change = rand::seq::index::sample(&mut self.rng, hand.len(), n).into_vec();
+        to_change.sort_unstable();
+
+        to_change
+    }
+}
+
+// Keeps every card that pairs with another one, and the wild cards.
+// Without pairs the two highest cards are kept.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct KeepPairs;
+
+impl Strategy for KeepPairs {
+    fn name(&self) -> &str {
+        "keep-pairs"
+    }
+
+    fn choose(&mut self, hand: &[Card], rules: &Rules) -> Vec<usize> {
+        let paired: Vec<usize> = (0..hand.len())
+            .filter(|i| {
+                let card = &hand[*i];
+                !rules.is_wild(card)
+                    && hand.iter().filter(|c| !rules.is_wild(c) && c.rank == card.rank).count() > 1
+            })
+            .collect();
+
+        if paired.is_empty() {
+            return change_lowest(hand, rules, &[], hand.len().saturating_sub(2));
+        }
+
+        change_lowest(hand, rules, &paired, MAX_CHANGES)
+    }
+}
+
+// A short list of rules of thumb, checked in order:
+// - keep any straight or better
+// - keep the matched cards of four of a kind, three of a kind and
+//   two pair
+// - keep four cards to a flush
+// - keep a pair of jacks or better
+// - keep four cards to a straight
+// - keep any other pair
+// - keep the two highest cards
+// Wild cards are always kept.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Chart;
+
+impl Strategy for Chart {
+    fn name(&self) -> &str {
+        "chart"
+    }
+
+    fn choose(&mut self, hand: &[Card], rules: &Rules) -> Vec<usize> {
+        let cards: &[Card; 5] = match hand.try_into() {
+            Ok(cards) => cards,
+            Err(_) => return vec![],
+        };
+        let naturals: Vec<usize> = (0..5).filter(|i| !rules.is_wild(&cards[*i])).collect();
+        let wilds = 5 - naturals.len();
+        let matched: Vec<usize> = naturals
+            .iter()
+            .filter(|i| naturals.iter().filter(|j| cards[**j].rank == cards[**i].rank).count() > 1)
+            .copied()
+            .collect();
+
+        match rules.evaluate(cards).category() {
+            HandCategory::HighCard | HandCategory::Pair => {}
+            HandCategory::TwoPair | HandCategory::ThreeOfAKind | HandCategory::FourOfAKind => {
+                return change_lowest(hand, rules, &matched, MAX_CHANGES);
+            }
+            _ => return vec![],
+        }
+
+        if let Some(suited) = four_to_flush(cards, &naturals, wilds) {
+            return change_lowest(hand, rules, &suited, MAX_CHANGES);
+        }
+
+        let high_pair = matched.iter().any(|i| cards[*i].rank >= Rank::Jack);
+        if high_pair {
+            return change_lowest(hand, rules, &matched, MAX_CHANGES);
+        }
+
+        if let Some(run) = four_to_straight(cards, &naturals, wilds) {
+            return change_lowest(hand, rules, &run, MAX_CHANGES);
+        }
+
+        if !matched.is_empty() {
+            return change_lowest(hand, rules, &matched, MAX_CHANGES);
+        }
+
+        change_lowest(hand, rules, &[], 3usize.saturating_sub(wilds))
+    }
+}
+
+// Always makes the choice worth the most points on average under the
+// pay table
+#[derive(Clone, Debug)]
+pub struct Optimal {
+    pay_table: PayTable,
+    // New deck for the rules of the last hand, built once
+    deck: Option<(Rules, Vec<Card>)>,
+}
+
+impl Optimal {
+    pub fn new(pay_table: PayTable) -> Optimal {
+        Optimal { pay_table, deck: None }
+    }
+}
+
+impl Strategy for Optimal {
+    fn name(&self) -> &str {
+        "optimal"
+    }
+
+    fn choose(&mut self, hand: &[Card], rules: &Rules) -> Vec<usize> {
+        if !matches!(&self.deck, Some((deck_rules, _)) if deck_rules == rules) {
+            self.deck = Some((*rules, rules.new_deck().remaining().to_vec()));
+        }
+
+        // Every card of a new deck that isn't in the hand can be drawn
+        let deck = self.deck.as_ref().map(|(_, cards)| &cards[..]).unwrap_or_default();
+        let remaining: Vec<Card> = deck.iter().filter(|c| !hand.contains(c)).copied().collect();
+
+        advisor::best_choice(hand, &remaining, rules, &self.pay_table)
+            .map(|best| best.to_change)
+            .unwrap_or_default()
+    }
+}
+
+// Changes up to n of the lowest cards that aren't kept or wild
+fn change_lowest(hand: &[Card], rules: &Rules, keep: &[usize], n: usize) -> Vec<usize> {
+    let mut others: Vec<usize> = (0..hand.len())
+        .filter(|i| !keep.contains(i) && !rules.is_wild(&hand[*i]))
+        .collect();
+    others.sort_by_key(|i| hand[*i].rank);
+    others.truncate(n.min(MAX_CHANGES));
+    others.sort_unstable();
+
+    others
+}
+
+// Natural cards of the suit that gets four cards with the wild ones
+fn four_to_flush(cards: &[Card; 5], naturals: &[usize], wilds: usize) -> Option<Vec<usize>> {
+    crate::Suit::iter()
+        .map(|suit| naturals.iter().filter(|i| cards[**i].suit == suit).copied().collect::<Vec<_>>())
+        .find(|suited| suited.len() + wilds >= 4)
+}
+
+// Natural cards, one per rank, that make four cards to a straight
+// with the wild ones
+fn four_to_straight(cards: &[Card; 5], naturals: &[usize], wilds: usize) -> Option<Vec<usize>> {
+    // The ace plays high and low
+    let value = |i: &usize, low_ace: bool| match cards[*i].rank {
+        Rank::Ace if low_ace => 1,
+        rank => rank.value(),
+    };
+
+    for high in (5..=Rank::Ace.value()).rev() {
+        let mut run: Vec<usize> = vec![];
+        for i in naturals {
+            let v = value(i, high == 5);
+            let new_rank = run.iter().all(|j| value(j, high == 5) != v);
+            if v + 4 >= high && v <= high && new_rank {
+                run.push(*i);
+            }
+        }
+
+        if run.len() + wilds >= 4 {
+            return Some(run);
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_hand;
+
+    fn choose(strategy: &mut dyn Strategy, s: &str) -> Vec<usize> {
+        strategy.choose(&parse_hand(s).unwrap(), &Rules::default())
+    }
+
+    #[test]
+    fn names() {
+        for name in NAMES {
+            let strategy = by_name(name, &PayTable::standard(), 0).unwrap();
+            assert_eq!(name, strategy.name());
+        }
+
+        assert!(by_name("lucky", &PayTable::standard(), 0).is_none());
+    }
+
+    #[test]
+    fn random() {
+        let mut strategy = RandomChoice::new(StdRng::seed_from_u64(3));
+
+        for _ in 0..50 {
+            let to_change = choose(&mut strategy, "Ah Kh Qh Jh 2c");
+            assert!(to_change.len() <= MAX_CHANGES);
+            assert!(to_change.iter().all(|i| *i < 5));
+            assert!(to_change.windows(2).all(|w| w[0] < w[1]));
+        }
+    }
+
+    #[test]
+    fn keep_pairs() {
+        assert_eq!(vec![1, 2, 4], choose(&mut KeepPairs, "9h 2c 5d 9s Kc"));
+        assert_eq!(vec![4], choose(&mut KeepPairs, "9h 9c 5d 5s Kc"));
+        assert_eq!(vec![0, 2, 4], choose(&mut KeepPairs, "2h Ac 5d Ks 8c"));
+    }
+
+    #[test]
+    fn chart() {
+        // Made hands
+        assert!(choose(&mut Chart, "2h 3h 4d 5s 6c").is_empty());
+        assert_eq!(vec![2, 4], choose(&mut Chart, "9h 9c 5d 9s Kc"));
+        // Four to a flush beats a low pair
+        assert_eq!(vec![0], choose(&mut Chart, "9h 9c 5c Qc Kc"));
+        // A high pair beats four to a straight
+        assert_eq!(vec![0, 1, 2], choose(&mut Chart, "9h Tc Qd Js Jc"));
+        // Four to a straight with the ace low
+        assert_eq!(vec![4], choose(&mut Chart, "Ah 2c 3d 4s 9c"));
+        // Nothing, the two highest cards are kept
+        assert_eq!(vec![0, 2, 4], choose(&mut Chart, "2h Ac 5d Ks 8c"));
+
+        // Wild cards are never changed
+        let deuces = Rules {
+            wild_rank: Some(Rank::Two),
+            ..Rules::default()
+        };
+        let to_change = Chart.choose(&parse_hand("2h 7c 9d Ks 4c").unwrap(), &deuces);
+        assert!(!to_change.contains(&0));
+    }
+
+    #[test]
+    fn optimal() {
+        let mut strategy = Optimal::new(PayTable::jacks_or_better());
+
+        assert_eq!(vec![4], choose(&mut strategy, "Ah Kh Qh Jh 2c"));
+        assert!(choose(&mut strategy, "Ah Kh Qh Jh Th").is_empty());
+    }
+}
@@ -0,0 +1,171 @@
+use crate::game::advisor::Outcomes;
+use crate::game::error::PokerError;
+use crate::game::poker::MAX_CHANGES;
+use crate::Card;
+
+// Choices closer than this to the best one are as good as the best
+const TOLERANCE: f64 = 1e-9;
+
+// One choice of cards to change compared with the best one
+#[derive(Clone, Debug, PartialEq)]
+pub struct Decision {
+    pub hand: Vec<Card>,
+    pub to_change: Vec<usize>,
+    pub best: Vec<usize>,
+    // Expected points of the player's choice and the best one
+    pub expected: f64,
+    pub best_expected: f64,
+}
+
+impl Decision {
+    // Expected points given away by the choice
+    pub fn cost(&self) -> f64 {
+        self.best_expected - self.expected
+    }
+
+    pub fn is_correct(&self) -> bool {
+        self.cost() < TOLERANCE
+    }
+}
+
+// Grades every choice of the session against the best one
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Trainer {
+    decisions: Vec<Decision>,
+}
+
+impl Trainer {
+    pub fn new() -> Trainer {
+        Trainer::default()
+    }
+
+    // Grades the cards the player chose to change before they are
+    // drawn, against every choice for the hand ranked by the advisor
+    pub fn grade(&mut self, hand: &[Card], to_change: &[usize], choices: &[Outcomes]) -> Result<&Decision, PokerError> {
+        let mut to_change = to_change.to_vec();
+        to_change.sort_unstable();
+
+        // The advisor leaves out the choices that change too many cards
+        let too_many = || PokerError::TooManyChanges {
+            requested: to_change.len(),
+            max: MAX_CHANGES,
+        };
+        let choice = choices.iter().find(|c| c.to_change == to_change).ok_or_else(too_many)?;
+        let best = &choices[0];
+
+        self.decisions.push(Decision {
+            hand: hand.to_vec(),
+            to_change,
+            best: best.to_change.clone(),
+            expected: choice.expected(),
+            best_expected: best.expected(),
+        });
+
+        Ok(&self.decisions[self.decisions.len() - 1])
+    }
+
+    pub fn decisions(&self) -> &[Decision] {
+        &self.decisions
+    }
+
+    pub fn correct(&self) -> usize {
+        self.decisions.iter().filter(|d| d.is_correct()).count()
+    }
+
+    // Share of correct choices, from 0 to 1
+    pub fn accuracy(&self) -> f64 {
+        if self.decisions.is_empty() {
+            return 0.0;
+        }
+
+        self.correct() as f64 / self.decisions.len() as f64
+    }
+
+    // Expected points given away over the session
+    pub fn total_cost(&self) -> f64 {
+        self.decisions.iter().map(|d| d.cost()).sum()
+    }
+
+    // Up to n mistakes, the most expensive first
+    pub fn worst(&self, n: usize) -> Vec<&Decision> {
+        let mut mistakes: Vec<&Decision> = self.decisions.iter().filter(|d| !d.is_correct()).collect();
+        mistakes.sort_by(|a, b| b.cost().total_cmp(&a.cost()));
+        mistakes.truncate(n);
+
+        mistakes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::advisor;
+    use crate::game::deck::Deck;
+    use crate::game::hand::HandCategory;
+    use crate::game::paytable::PayTable;
+    use crate::game::rules::Rules;
+    use crate::parse_hand;
+
+    fn grade(trainer: &mut Trainer, s: &str, to_change: &[usize]) -> Decision {
+        let hand = parse_hand(s).unwrap();
+        let deck: Vec<Card> = Deck::new()
+            .remaining()
+            .iter()
+            .filter(|c| !hand.contains(c))
+            .copied()
+            .collect();
+
+        let choices = advisor::rank_choices(&hand, &deck, &Rules::default(), &PayTable::jacks_or_better()).unwrap();
+
+        trainer.grade(&hand, to_change, &choices).unwrap().clone()
+    }
+
+    #[test]
+    fn grading() {
+        let mut trainer = Trainer::new();
+
+        let decision = grade(&mut trainer, "Ah Kh Qh Jh Th", &[]);
+        assert!(decision.is_correct());
+        assert_eq!(0.0, decision.cost());
+
+        // Breaking up the royal flush for a pair
+        let decision = grade(&mut trainer, "Ah Kh Qh Jh Th", &[4, 3, 2]);
+        assert!(!decision.is_correct());
+        assert_eq!(vec![2, 3, 4], decision.to_change);
+        assert!(decision.best.is_empty());
+        assert!(decision.cost() > 240.0);
+
+        assert_eq!(2, trainer.decisions().len());
+        assert_eq!(1, trainer.correct());
+        assert_eq!(0.5, trainer.accuracy());
+
+        // The advisor doesn't rank changing four cards
+        let choices = [advisor::Outcomes {
+            to_change: vec![],
+            counts: [0; HandCategory::ALL.len()],
+            points: 0,
+        }];
+        assert_eq!(
+            Some(PokerError::TooManyChanges { requested: 4, max: 3 }),
+            trainer.grade(&parse_hand("Ah Kh Qh Jh Th").unwrap(), &[0, 1, 2, 3], &choices).err()
+        );
+        assert_eq!(2, trainer.decisions().len());
+    }
+
+    #[test]
+    fn worst_mistakes() {
+        let mut trainer = Trainer::new();
+        assert_eq!(0.0, trainer.accuracy());
+
+        grade(&mut trainer, "Ah Kh Qh Jh 2c", &[0]);
+        grade(&mut trainer, "Ah Kh Qh Jh Th", &[0]);
+        grade(&mut trainer, "Ah Kh Qh Jh 2c", &[4]);
+
+        let worst = trainer.worst(5);
+        assert_eq!(2, worst.len());
+        assert_eq!(parse_hand("Ah Kh Qh Jh Th").unwrap(), worst[0].hand);
+        assert!(worst[0].cost() > worst[1].cost());
+        assert_eq!(1, trainer.worst(1).len());
+        assert!((trainer.total_cost() - worst[0].cost() - worst[1].cost()).abs() < 1e-9);
+    }
+}
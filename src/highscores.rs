@@ -0,0 +1,328 @@
+use crate::date::Date;
+use crate::game::paytable::PayTable;
+use crate::storage::{self, StorageError};
+use std::cmp::Reverse;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+// Entries kept in each table
+pub const TABLE_SIZE: usize = 10;
+
+// Longest player name, longer ones are cut
+pub const MAX_NAME_LEN: usize = 16;
+
+const FILE_NAME: &str = "highscores.txt";
+
+// How a game was played. Each mode has its own tables.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Mode {
+    Classic,
+    Trainer,
+    Daily,
+    // A strategy played some of the hands
+    Autoplay,
+}
+
+impl Mode {
+    pub const ALL: [Mode; 4] = [Mode::Classic, Mode::Trainer, Mode::Daily, Mode::Autoplay];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            Mode::Classic => "classic",
+            Mode::Trainer => "trainer",
+            Mode::Daily => "daily",
+            Mode::Autoplay => "autoplay",
+        }
+    }
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.key())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HighScore {
+    pub name: String,
+    pub date: Date,
+    pub mode: Mode,
+    // Name of the pay table, as shown
+    pub pay_table: String,
+    // What the pay table pays (see `PayTable::id`), tables with the same
+    // name can pay differently
+    pub pay_table_id: String,
+    // Description of the rules, as shown on the welcome screen
+    pub rules: String,
+    pub hands: u32,
+    pub score: i32,
+}
+
+impl HighScore {
+    // Whether the entry belongs to the table of the mode, pay table id
+    // and rules, scores of different games are never compared
+    pub fn is_in_table(&self, mode: Mode, pay_table_id: &str, rules: &str) -> bool {
+        self.mode == mode && self.pay_table_id == pay_table_id && self.rules == rules
+    }
+}
+
+// Best scores of every table, kept in a text file with one entry per
+// line and the fields separated by tabs:
+//
+//     # name  date  mode  pay table  pay table id  rules  hands  score
+//     Ana     2024-03-09  classic  Standard  67516319911d8963  No wild cards  12  40
+//
+// Only the best TABLE_SIZE entries of each table are kept. Ties go to
+// the fewest hands, then to the earliest score. Lines without the pay
+// table id, from before it was kept, belong to the preset of their pay
+// table name.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HighScores {
+    entries: Vec<HighScore>,
+}
+
+impl HighScores {
+    pub fn default_path() -> Result<PathBuf, StorageError> {
+        Ok(storage::data_dir()?.join(FILE_NAME))
+    }
+
+    // No scores yet if the file doesn't exist
+    pub fn load(path: &Path) -> Result<HighScores, StorageError> {
+        match storage::read_file(path)? {
+            Some(contents) => HighScores::parse(&contents),
+            None => Ok(HighScores::default()),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), StorageError> {
+        storage::write_file(path, &self.to_file_string())
+    }
+
+    pub fn parse(contents: &str) -> Result<HighScores, StorageError> {
+        let mut scores = HighScores::default();
+
+        for (n, line) in contents.lines().enumerate() {
+            let error = |message: String| StorageError::Parse { line: n + 1, message };
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.split('\t').map(|f| f.trim()).collect();
+            let (name, date, mode, pay_table, pay_table_id, rules, hands, score) = match fields[..] {
+                [name, date, mode, pay_table, id, rules, hands, score] => {
+                    (name, date, mode, pay_table, id.to_string(), rules, hands, score)
+                }
+                [name, date, mode, pay_table, rules, hands, score] => {
+                    let id = PayTable::preset_id(pay_table).unwrap_or_default();
+                    (name, date, mode, pay_table, id, rules, hands, score)
+                }
+                _ => {
+                    let message = format!("expected 8 fields separated by tabs, got {}", fields.len());
+                    return Err(error(message));
+                }
+            };
+
+            scores.entries.push(HighScore {
+                name: name.to_string(),
+                date: date.parse().map_err(|e| error(format!("{}", e)))?,
+                mode: Mode::ALL
+                    .into_iter()
+                    .find(|m| m.key() == mode)
+                    .ok_or_else(|| error(format!("unknown mode \"{}\"", mode)))?,
+                pay_table: pay_table.to_string(),
+                pay_table_id,
+                rules: rules.to_string(),
+                hands: hands.parse().map_err(|_| error(format!("invalid hands \"{}\"", hands)))?,
+                score: score.parse().map_err(|_| error(format!("invalid score \"{}\"", score)))?,
+            });
+        }
+
+        scores.sort();
+        Ok(scores)
+    }
+
+    pub fn to_file_string(&self) -> String {
+        let mut s = "# name\tdate\tmode\tpay table\tpay table id\trules\thands\tscore\n".to_string();
+
+        for e in &self.entries {
+            s.push_str(&format!(
+                "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
+                e.name, e.date, e.mode, e.pay_table, e.pay_table_id, e.rules, e.hands, e.score
+            ));
+        }
+
+        s
+    }
+
+    // Adds the score to its table and returns its place from 1, or None
+    // if it didn't make it
+    pub fn record(&mut self, mut entry: HighScore) -> Option<usize> {
+        entry.name = clean_name(&entry.name);
+        let table = |e: &HighScore| e.is_in_table(entry.mode, &entry.pay_table_id, &entry.rules);
+
+        // New entries go after older ones with the same score
+        let place = self
+            .entries
+            .iter()
+            .filter(|e| table(e) && sort_key(e) <= sort_key(&entry))
+            .count();
+        if place >= TABLE_SIZE {
+            return None;
+        }
+
+        self.entries.push(entry.clone());
+        self.sort();
+
+        // The entry that fell off the bottom of the table goes
+        let mut in_table = 0;
+        self.entries.retain(|e| {
+            if !table(e) {
+                return true;
+            }
+            in_table += 1;
+            in_table <= TABLE_SIZE
+        });
+
+        Some(place + 1)
+    }
+
+    // Best entries of a table, best first
+    pub fn top(&self, mode: Mode, pay_table_id: &str, rules: &str, n: usize) -> Vec<&HighScore> {
+        self.entries
+            .iter()
+            .filter(|e| e.is_in_table(mode, pay_table_id, rules))
+            .take(n)
+            .collect()
+    }
+
+    pub fn entries(&self) -> &[HighScore] {
+        &self.entries
+    }
+
+    // Stable, so equal entries stay in the order they were recorded
+    fn sort(&mut self) {
+        self.entries.sort_by_key(sort_key);
+    }
+}
+
+fn sort_key(e: &HighScore) -> (Reverse<i32>, u32, Date) {
+    (Reverse(e.score), e.hands, e.date)
+}
+
+// Names are one short line without tabs, and don't start with the #
+// of the file's comment lines
+pub fn clean_name(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .filter(|c| !c.is_control())
+        .take(MAX_NAME_LEN)
+        .collect();
+
+    match name.trim().trim_start_matches('#').trim() {
+        "" => "Player".to_string(),
+        name => name.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn standard() -> String {
+        PayTable::standard().id()
+    }
+
+    fn entry(name: &str, score: i32, hands: u32) -> HighScore {
+        HighScore {
+            name: name.to_string(),
+            date: Date::new(2024, 3, 9).unwrap(),
+            mode: Mode::Classic,
+            pay_table: "Standard".to_string(),
+            pay_table_id: standard(),
+            rules: "No wild cards".to_string(),
+            hands,
+            score,
+        }
+    }
+
+    #[test]
+    fn tables() {
+        let mut scores = HighScores::default();
+
+        assert_eq!(Some(1), scores.record(entry("Ana", 40, 12)));
+        assert_eq!(Some(2), scores.record(entry("Bo", 30, 12)));
+        // Fewer hands for the same score is better
+        assert_eq!(Some(2), scores.record(entry("Cy", 30, 8)));
+        // Ties with older entries go after them
+        assert_eq!(Some(4), scores.record(entry("Di", 30, 12)));
+
+        // Other modes and rules have their own tables
+        let daily = HighScore { mode: Mode::Daily, ..entry("Ed", 5, 10) };
+        assert_eq!(Some(1), scores.record(daily));
+        let wilds = HighScore { rules: "Deuces wild".to_string(), ..entry("Flo", 1, 1) };
+        assert_eq!(Some(1), scores.record(wilds));
+        // So does a custom table with the name of a preset
+        let custom = HighScore { pay_table_id: "custom".to_string(), ..entry("Gus", 1, 1) };
+        assert_eq!(Some(1), scores.record(custom));
+
+        let names: Vec<&str> = scores
+            .top(Mode::Classic, &standard(), "No wild cards", 3)
+            .iter()
+            .map(|e| e.name.as_str())
+            .collect();
+        assert_eq!(vec!["Ana", "Cy", "Bo"], names);
+        assert_eq!(1, scores.top(Mode::Daily, &standard(), "No wild cards", 10).len());
+        assert!(scores.top(Mode::Trainer, &standard(), "No wild cards", 10).is_empty());
+    }
+
+    #[test]
+    fn full_tables() {
+        let mut scores = HighScores::default();
+        for score in 1..=TABLE_SIZE as i32 {
+            scores.record(entry("Ana", score, 5));
+        }
+
+        assert_eq!(None, scores.record(entry("Bo", 0, 5)));
+        assert_eq!(Some(TABLE_SIZE - 1), scores.record(entry("Bo", 2, 4)));
+
+        // The lowest score fell off the table
+        let table = scores.top(Mode::Classic, &standard(), "No wild cards", 20);
+        assert_eq!(TABLE_SIZE, table.len());
+        assert_eq!("Bo", table[TABLE_SIZE - 2].name);
+        assert!(table.iter().all(|e| e.score >= 2));
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!("Ana", clean_name("  Ana "));
+        assert_eq!("Player", clean_name("\t"));
+        assert_eq!("AnaBo", clean_name("Ana\tBo"));
+        assert_eq!("1 Ana #2", clean_name("#1 Ana #2"));
+        assert_eq!("Player", clean_name("##"));
+        assert_eq!(MAX_NAME_LEN, clean_name(&"x".repeat(40)).len());
+    }
+
+    #[test]
+    fn file_round_trip() {
+        let mut scores = HighScores::default();
+        scores.record(entry("Ana Maria", 40, 12));
+        scores.record(entry("#1", 20, 12));
+        scores.record(HighScore { mode: Mode::Autoplay, ..entry("Bo", -3, 2) });
+
+        let contents = scores.to_file_string();
+        assert_eq!(Ok(scores), HighScores::parse(&contents));
+
+        assert!(HighScores::parse("Ana\t2024-03-09\tclassic\tStandard\t12\t40").is_err());
+
+        // Older lines without the id belong to the preset of the name
+        let old = HighScores::parse("Ana\t2024-03-09\tclassic\tStandard\tNo wild cards\t12\t40").unwrap();
+        assert_eq!(standard(), old.entries()[0].pay_table_id);
+        assert_eq!(
+            Err(StorageError::Parse {
+                line: 1,
+                message: "unknown mode \"arcade\"".to_string()
+            }),
+            HighScores::parse("Ana\t2024-03-09\tarcade\tStandard\tNo wild cards\t12\t40")
+        );
+    }
+}
@@ -0,0 +1,432 @@
+use crate::date::Date;
+use crate::game::hand::HandCategory;
+use crate::game::state::HandResult;
+use crate::storage::{self, StorageError};
+use crate::{parse_hand, Card};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+const FILE_NAME: &str = "history.txt";
+
+// First words of every hand, they also tell hands apart in a file
+const HEADER: &str = "Single Player Poker Hand #";
+
+// One hand as written in the hand history. Hands follow each other in
+// the file with a blank line in between, in the style of the hand
+// histories of online poker rooms:
+//
+//     Single Player Poker Hand #3: Jacks or Better 9/6 - 2024-03-09
+//     Rules: No wild cards
+//     Seed: 1234
+//     *** DEAL ***
+//     Dealt [6d 5c Ac As 3d]
+//     *** DRAW ***
+//     Discards 3 [6d 5c 3d] at 1 2 5
+//     Draws [Kh 2s 9c]
+//     *** SUMMARY ***
+//     Final hand [Kh 2s Ac As 9c] (Pair)
+//     Points: 1
+//     Score: 12
+//
+// The number counts the hands of a session, which the seed identifies.
+// The discards are followed by their positions in the dealt hand,
+// counted from 1. A hand where no card was changed has `Stands pat` in
+// place of the discards and draws. Cards are written in the usual
+// notation: rank then suit letter, T for tens and Jk for jokers. The
+// notation doesn't tell the jokers or the decks of a shoe apart, so
+// discards written without positions are matched to the first dealt
+// cards like them. Lines that don't start with a known word are
+// ignored by the parser, so notes can be added by hand.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HandRecord {
+    pub number: u32,
+    pub date: Date,
+    pub pay_table: String,
+    // Description of the rules, as shown on the welcome screen
+    pub rules: String,
+    pub seed: u64,
+    pub dealt: Vec<Card>,
+    // Positions of the discarded cards in the dealt hand, in the order
+    // they were selected
+    pub to_change: Vec<usize>,
+    // Cards thrown away, in the same order
+    pub discarded: Vec<Card>,
+    // Cards drawn in their place, in the same order
+    pub drawn: Vec<Card>,
+    pub final_hand: Vec<Card>,
+    pub category: HandCategory,
+    pub points: i32,
+    // Score of the session after the hand
+    pub score: i32,
+}
+
+impl HandRecord {
+    // Record of a hand that just ended in the result, with the final
+    // hand of the game
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        number: u32,
+        date: Date,
+        pay_table: &str,
+        rules: &str,
+        seed: u64,
+        final_hand: &[Card],
+        result: &HandResult,
+        score: i32,
+    ) -> HandRecord {
+        // The dealt cards were where the drawn ones are now
+        let mut dealt = final_hand.to_vec();
+        for (i, card) in result.to_change.iter().zip(&result.discarded) {
+            if let Some(slot) = dealt.get_mut(*i) {
+                *slot = *card;
+            }
+        }
+
+        HandRecord {
+            number,
+            date,
+            pay_table: pay_table.to_string(),
+            rules: rules.to_string(),
+            seed,
+            dealt,
+            to_change: result.to_change.clone(),
+            discarded: result.discarded.clone(),
+            drawn: result.replacements.clone(),
+            final_hand: final_hand.to_vec(),
+            category: result.value.category(),
+            points: result.points,
+            score,
+        }
+    }
+}
+
+impl fmt::Display for HandRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}{}: {} - {}", HEADER, self.number, self.pay_table, self.date)?;
+        writeln!(f, "Rules: {}", self.rules)?;
+        writeln!(f, "Seed: {}", self.seed)?;
+        writeln!(f, "*** DEAL ***")?;
+        writeln!(f, "Dealt [{}]", cards(&self.dealt))?;
+        writeln!(f, "*** DRAW ***")?;
+        if self.discarded.is_empty() {
+            writeln!(f, "Stands pat")?;
+        } else {
+            let positions: Vec<String> = self.to_change.iter().map(|i| (i + 1).to_string()).collect();
+            writeln!(
+                f,
+                "Discards {} [{}] at {}",
+                self.discarded.len(),
+                cards(&self.discarded),
+                positions.join(" ")
+            )?;
+            writeln!(f, "Draws [{}]", cards(&self.drawn))?;
+        }
+        writeln!(f, "*** SUMMARY ***")?;
+        writeln!(f, "Final hand [{}] ({})", cards(&self.final_hand), self.category.name())?;
+        writeln!(f, "Points: {}", self.points)?;
+        write!(f, "Score: {}", self.score)
+    }
+}
+
+fn cards(cards: &[Card]) -> String {
+    cards.iter().map(|c| c.to_string()).collect::<Vec<_>>().join(" ")
+}
+
+pub fn default_path() -> Result<PathBuf, StorageError> {
+    Ok(storage::data_dir()?.join(FILE_NAME))
+}
+
+// Adds the hand to the end of the history
+pub fn append(path: &Path, record: &HandRecord) -> Result<(), StorageError> {
+    storage::append_file(path, &format!("{}\n\n", record))
+}
+
+// Every hand of the history, in the order they were written
+pub fn load(path: &Path) -> Result<Vec<HandRecord>, StorageError> {
+    match storage::read_file(path)? {
+        Some(contents) => parse(&contents),
+        None => Ok(vec![]),
+    }
+}
+
+pub fn parse(contents: &str) -> Result<Vec<HandRecord>, StorageError> {
+    let mut records = vec![];
+    let mut record: Option<HandRecord> = None;
+    let mut start = 0;
+
+    for (n, line) in contents.lines().enumerate() {
+        let error = |message: String| StorageError::Parse { line: n + 1, message };
+        let line = line.trim();
+
+        if let Some(header) = line.strip_prefix(HEADER) {
+            records.extend(record.take().map(|r| check(r, start)).transpose()?);
+            record = Some(parse_header(header).ok_or_else(|| error(format!("invalid header \"{}\"", line)))?);
+            start = n + 1;
+            continue;
+        }
+
+        let Some(r) = record.as_mut() else {
+            continue;
+        };
+        let invalid = || error(format!("invalid line \"{}\"", line));
+
+        if let Some(rules) = line.strip_prefix("Rules: ") {
+            r.rules = rules.to_string();
+        } else if let Some(seed) = line.strip_prefix("Seed: ") {
+            r.seed = seed.parse().map_err(|_| invalid())?;
+        } else if let Some(rest) = line.strip_prefix("Dealt ") {
+            r.dealt = bracketed(rest).ok_or_else(invalid)?;
+        } else if let Some(rest) = line.strip_prefix("Discards ") {
+            let (count, rest) = rest.split_once(' ').ok_or_else(invalid)?;
+            // Hand-written lines can leave out the positions
+            let (discarded, positions) = match rest.split_once("] at ") {
+                Some((discarded, positions)) => (format!("{}]", discarded), positions),
+                None => (rest.to_string(), ""),
+            };
+            r.discarded = bracketed(&discarded).ok_or_else(invalid)?;
+            r.to_change = positions
+                .split_whitespace()
+                .map(|p| p.parse::<usize>().ok().filter(|p| (1..=5).contains(p)).map(|p| p - 1))
+                .collect::<Option<_>>()
+                .ok_or_else(invalid)?;
+            if count.parse() != Ok(r.discarded.len()) {
+                return Err(invalid());
+            }
+        } else if let Some(rest) = line.strip_prefix("Draws ") {
+            r.drawn = bracketed(rest).ok_or_else(invalid)?;
+        } else if line == "Stands pat" {
+            r.to_change.clear();
+            r.discarded.clear();
+            r.drawn.clear();
+        } else if let Some(rest) = line.strip_prefix("Final hand ") {
+            let (hand, category) = rest.split_once("] (").ok_or_else(invalid)?;
+            r.final_hand = bracketed(&format!("{}]", hand)).ok_or_else(invalid)?;
+            let category = category.strip_suffix(')').ok_or_else(invalid)?;
+            r.category = HandCategory::ALL
+                .into_iter()
+                .find(|c| c.name() == category)
+                .ok_or_else(|| error(format!("unknown hand \"{}\"", category)))?;
+        } else if let Some(points) = line.strip_prefix("Points: ") {
+            r.points = points.parse().map_err(|_| invalid())?;
+        } else if let Some(score) = line.strip_prefix("Score: ") {
+            r.score = score.parse().map_err(|_| invalid())?;
+        }
+    }
+
+    records.extend(record.map(|r| check(r, start)).transpose()?);
+
+    Ok(records)
+}
+
+// "Single Player Poker Hand #" is already gone from the header
+fn parse_header(header: &str) -> Option<HandRecord> {
+    let (number, rest) = header.split_once(": ")?;
+    let (pay_table, date) = rest.rsplit_once(" - ")?;
+
+    Some(HandRecord {
+        number: number.parse().ok()?,
+        date: date.parse().ok()?,
+        pay_table: pay_table.to_string(),
+        rules: String::new(),
+        seed: 0,
+        dealt: vec![],
+        to_change: vec![],
+        discarded: vec![],
+        drawn: vec![],
+        final_hand: vec![],
+        category: HandCategory::HighCard,
+        points: 0,
+        score: 0,
+    })
+}
+
+fn bracketed(s: &str) -> Option<Vec<Card>> {
+    let inner = s.trim().strip_prefix('[')?.strip_suffix(']')?;
+
+    parse_hand(inner).ok()
+}
+
+// A hand needs its cards, and as many drawn as discarded from
+// different places
+fn check(mut record: HandRecord, line: usize) -> Result<HandRecord, StorageError> {
+    let error = |message: &str| StorageError::Parse {
+        line,
+        message: format!("hand #{} {}", record.number, message),
+    };
+
+    if record.dealt.len() != 5 || record.final_hand.len() != 5 {
+        return Err(error("needs 5 dealt cards and a final hand"));
+    }
+    if record.discarded.len() != record.drawn.len() {
+        return Err(error("has to draw as many cards as it discards"));
+    }
+
+    // Discards without positions take the first dealt cards like them
+    if record.to_change.is_empty() {
+        let mut used = [false; 5];
+        for card in &record.discarded {
+            let Some(i) = (0..5).find(|i| !used[*i] && record.dealt[*i] == *card) else {
+                return Err(error("discards a card it wasn't dealt"));
+            };
+            used[i] = true;
+            record.to_change.push(i);
+        }
+    }
+    if record.to_change.len() != record.discarded.len() {
+        return Err(error("needs a position for each discard"));
+    }
+    for (n, i) in record.to_change.iter().enumerate() {
+        if record.to_change[..n].contains(i) {
+            return Err(error("discards the same position twice"));
+        }
+    }
+
+    Ok(record)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::paytable::PayTable;
+    use crate::game::rules::Rules;
+    use crate::game::state::{Action, GameState};
+
+    // Plays a hand of a new session changing the cards at the positions
+    fn record(to_change: Vec<usize>) -> HandRecord {
+        let state = GameState::new(Rules::default(), PayTable::jacks_or_better(), 42);
+        let (state, _) = state.update(Action::Deal).unwrap();
+        let (state, _) = state.update(Action::Select(to_change)).unwrap();
+        let (state, _) = state.update(Action::Draw).unwrap();
+
+        HandRecord::new(
+            state.hands_played(),
+            Date::new(2024, 3, 9).unwrap(),
+            &state.pay_table().name,
+            &state.rules().to_string(),
+            state.seed(),
+            state.hand(),
+            state.result().unwrap(),
+            state.score(),
+        )
+    }
+
+    #[test]
+    fn format() {
+        // Seed 42 deals 6h Jc 5c Jd 7s
+        let record = record(vec![4, 0, 1]);
+        let text = record.to_string();
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert_eq!("Single Player Poker Hand #1: Jacks or Better 9/6 - 2024-03-09", lines[0]);
+        assert_eq!("Rules: No wild cards", lines[1]);
+        assert_eq!("Seed: 42", lines[2]);
+        assert_eq!("Dealt [6h Jc 5c Jd 7s]", lines[4]);
+        assert_eq!("Discards 3 [7s 6h Jc] at 5 1 2", lines[6]);
+        assert!(lines[7].starts_with("Draws ["));
+        assert!(lines[9].starts_with("Final hand ["));
+        assert_eq!(vec![4, 0, 1], record.to_change);
+        assert_eq!(&record.final_hand[2..4], &record.dealt[2..4]);
+
+        let pat = super::tests::record(vec![]);
+        assert!(pat.to_string().contains("*** DRAW ***\nStands pat\n*** SUMMARY ***"));
+        assert_eq!(pat.dealt, pat.final_hand);
+    }
+
+    #[test]
+    fn round_trip() {
+        let records = vec![record(vec![0, 1, 4]), record(vec![]), record(vec![2])];
+        let text: String = records.iter().map(|r| format!("{}\n\n", r)).collect();
+
+        assert_eq!(Ok(records), parse(&text));
+    }
+
+    #[test]
+    fn hand_written() {
+        let text = "\
+Notes from a good night
+
+Single Player Poker Hand #7: Standard - 2024-03-09
+Rules: Short deck (6+), 1 joker wild
+Seed: 5
+*** DEAL ***
+Dealt [Jk 9h 9c Ts 6d]
+*** DRAW ***
+Discards 2 [Ts 6d]
+Draws [9s 7c]
+*** SUMMARY ***
+Final hand [Jk 9h 9c 9s 7c] (Four of a Kind)
+Points: 20
+Score: 31
+Great hand!
+";
+        let records = parse(text).unwrap();
+
+        assert_eq!(1, records.len());
+        let r = &records[0];
+        assert_eq!(7, r.number);
+        assert_eq!("Standard", r.pay_table);
+        assert_eq!("Short deck (6+), 1 joker wild", r.rules);
+        assert!(r.dealt[0].joker);
+        assert_eq!(vec![3, 4], r.to_change);
+        assert_eq!(HandCategory::FourOfAKind, r.category);
+        assert_eq!((20, 31), (r.points, r.score));
+    }
+
+    #[test]
+    fn two_jokers() {
+        // The second joker is thrown away, the notation can't tell it
+        // from the first one
+        let dealt = parse_hand("Jk 9h Jk 9c 4d").unwrap();
+        let final_hand = parse_hand("Jk 9h 9s 9c 4d").unwrap();
+        let rules = Rules { jokers: 2, ..Rules::default() };
+        let result = HandResult {
+            to_change: vec![2],
+            discarded: vec![dealt[2]],
+            replacements: vec![final_hand[2]],
+            value: rules.evaluate(&final_hand.clone().try_into().unwrap()),
+            points: 25,
+        };
+        let date = Date::new(2024, 3, 9).unwrap();
+        let record = HandRecord::new(3, date, "Standard", &rules.to_string(), 8, &final_hand, &result, 40);
+
+        assert_eq!(dealt, record.dealt);
+        assert!(record.to_string().contains("Discards 1 [Jk] at 3\n"));
+        let parsed = parse(&record.to_string()).unwrap();
+        assert_eq!(vec![2], parsed[0].to_change);
+        assert_eq!(vec![record], parsed);
+
+        // Without the positions the jokers are matched in order
+        let text = "\
+Single Player Poker Hand #3: Standard - 2024-03-09
+Dealt [Jk 9h Jk 9c 4d]
+Discards 2 [Jk Jk]
+Draws [9s 9d]
+Final hand [9s 9h 9d 9c 4d] (Four of a Kind)
+";
+        assert_eq!(vec![0, 2], parse(text).unwrap()[0].to_change);
+    }
+
+    #[test]
+    fn bad_histories() {
+        let record = record(vec![1]);
+        let text = record.to_string();
+
+        assert!(parse(&text.replace("Discards 1", "Discards 2")).is_err());
+        assert!(parse(&text.replace(" at 2", " at 6")).is_err());
+        assert!(parse(&text.replace(" at 2", " at 2 3")).is_err());
+        assert!(parse(&text.replace(" at 2", " at two")).is_err());
+        assert!(parse(&text.replace("Seed: 42", "Seed: lots")).is_err());
+        assert!(parse(&text.replace(&format!("({})", record.category), "(Lucky)")).is_err());
+        assert!(parse(&text.replace("Hand #1:", "Hand #one:")).is_err());
+
+        let mut lines: Vec<&str> = text.lines().collect();
+        lines.retain(|l| !l.starts_with("Draws"));
+        assert_eq!(
+            Err(StorageError::Parse {
+                line: 1,
+                message: "hand #1 has to draw as many cards as it discards".to_string()
+            }),
+            parse(&lines.join("\n"))
+        );
+    }
+}
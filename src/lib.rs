@@ -1,47 +1,356 @@
-#[derive(Clone, Debug, PartialEq)]
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+pub mod config;
+pub mod daily;
+pub mod date;
+pub mod game;
+pub mod highscores;
+pub mod history;
+pub mod save;
+pub mod stats;
+pub mod storage;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Suit {
+    Spades,
+    Hearts,
+    Diamonds,
+    Clubs,
+}
+
+impl Suit {
+    pub const ALL: [Suit; 4] = [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs];
+
+    pub fn iter() -> impl Iterator<Item = Suit> {
+        Suit::ALL.into_iter()
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Suit::Spades => "Spades",
+            Suit::Hearts => "Hearts",
+            Suit::Diamonds => "Diamonds",
+            Suit::Clubs => "Clubs",
+        }
+    }
+
+    // Lowercase letter used in card notation
+    pub fn letter(&self) -> char {
+        match self {
+            Suit::Spades => 's',
+            Suit::Hearts => 'h',
+            Suit::Diamonds => 'd',
+            Suit::Clubs => 'c',
+        }
+    }
+
+    pub fn symbol(&self) -> char {
+        match self {
+            Suit::Spades => '♠',
+            Suit::Hearts => '♥',
+            Suit::Diamonds => '♦',
+            Suit::Clubs => '♣',
+        }
+    }
+}
+
+impl fmt::Display for Suit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+// Ranks are ordered with the ace high. Code that needs the ace low
+// (wheel straights) handles it explicitly.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Rank {
+    Two = 2,
+    Three,
+    Four,
+    Five,
+    Six,
+    Seven,
+    Eight,
+    Nine,
+    Ten,
+    Jack,
+    Queen,
+    King,
+    Ace,
+}
+
+impl Rank {
+    pub const ALL: [Rank; 13] = [
+        Rank::Two,
+        Rank::Three,
+        Rank::Four,
+        Rank::Five,
+        Rank::Six,
+        Rank::Seven,
+        Rank::Eight,
+        Rank::Nine,
+        Rank::Ten,
+        Rank::Jack,
+        Rank::Queen,
+        Rank::King,
+        Rank::Ace,
+    ];
+
+    pub fn iter() -> impl Iterator<Item = Rank> {
+        Rank::ALL.into_iter()
+    }
+
+    // Numeric value from 2 to 14, the ace being 14
+    pub fn value(&self) -> u8 {
+        *self as u8
+    }
+
+    pub fn from_value(value: u8) -> Option<Rank> {
+        match value {
+            2..=14 => Some(Rank::ALL[(value - 2) as usize]),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Rank::Two => "Two",
+            Rank::Three => "Three",
+            Rank::Four => "Four",
+            Rank::Five => "Five",
+            Rank::Six => "Six",
+            Rank::Seven => "Seven",
+            Rank::Eight => "Eight",
+            Rank::Nine => "Nine",
+            Rank::Ten => "Ten",
+            Rank::Jack => "Jack",
+            Rank::Queen => "Queen",
+            Rank::King => "King",
+            Rank::Ace => "Ace",
+        }
+    }
+
+    // Single character used in card notation, the ten being 'T'
+    pub fn letter(&self) -> char {
+        match self {
+            Rank::Ten => 'T',
+            Rank::Jack => 'J',
+            Rank::Queen => 'Q',
+            Rank::King => 'K',
+            Rank::Ace => 'A',
+            _ => (b'0' + self.value()) as char,
+        }
+    }
+}
+
+// Ranks are displayed the way they are printed on a card
+impl fmt::Display for Rank {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Rank::Ten => f.write_str("10"),
+            _ => write!(f, "{}", self.letter()),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Card {
-    pub suit: String,
-    pub rank: u8,
-    pub value: u8,
+    pub rank: Rank,
+    pub suit: Suit,
+    // Jokers have no rank or suit of their own. They are stored as
+    // aces with the suit telling one joker from another.
+    pub joker: bool,
+    // Deck of the shoe the card comes from, so the same card of two
+    // decks are still different cards
+    pub deck: u8,
 }
 
+// Most decks that can be shuffled together in a shoe
+pub const MAX_DECKS: u8 = 8;
+
 impl Card {
-    pub fn new(v: u8) -> Card {
-        let value = v;
-
-        let suit = match (value - 1) / 13 {
-            0 => "Spades".to_string(),
-            1 => "Hearts".to_string(),
-            2 => "Diamonds".to_string(),
-            3 => "Clubs".to_string(),
-            _ => panic!("Invalid suit value: {}", (value  -1) / 13),
-        };
+    pub fn new(rank: Rank, suit: Suit) -> Card {
+        Card {
+            rank,
+            suit,
+            joker: false,
+            deck: 0,
+        }
+    }
+
+    // Up to 4 different jokers, numbered from 0
+    pub fn joker(index: u8) -> Card {
+        Card {
+            rank: Rank::Ace,
+            suit: Suit::ALL[index as usize % 4],
+            joker: true,
+            deck: 0,
+        }
+    }
+
+    // Same card from another deck of the shoe
+    pub fn in_deck(self, deck: u8) -> Card {
+        Card { deck, ..self }
+    }
+
+    // Cards are numbered from 1 to 52, suit by suit in the order
+    // spades, hearts, diamonds and clubs, from the ace to the king.
+    // Jokers follow from 53. Each deck of a shoe takes the next 56
+    // numbers, so the first card of the second deck is 57.
+    pub fn from_value(value: u16) -> Option<Card> {
+        if value == 0 || value > MAX_DECKS as u16 * 56 {
+            return None;
+        }
+
+        let deck = ((value - 1) / 56) as u8;
+        let value = ((value - 1) % 56 + 1) as u8;
+
+        if (53..=56).contains(&value) {
+            return Some(Card::joker(value - 53).in_deck(deck));
+        }
 
-        let rank: u8 = if let 0 = value % 13 {
-            13
-        } else {
-            value % 13
+        let suit = Suit::ALL[((value - 1) / 13) as usize];
+        let rank = match (value - 1) % 13 {
+            0 => Rank::Ace,
+            r => Rank::ALL[(r - 1) as usize],
         };
 
-        Card { suit, rank, value }
+        Some(Card::new(rank, suit).in_deck(deck))
     }
 
-    pub fn get_card(&self) -> (String, String) {
+    pub fn value(&self) -> u16 {
+        let deck = self.deck as u16 * 56;
+        if self.joker {
+            return deck + 53 + self.suit as u16;
+        }
+
         let rank = match self.rank {
-            1 => "A".to_string(),
-            2..=10 => self.rank.to_string(),
-            11 => "J".to_string(),
-            12 => "Q".to_string(),
-            13 => "K".to_string(),
-            _ => panic!("Invalid rank: {}", self.rank),
+            Rank::Ace => 1,
+            r => r.value(),
         };
 
-        (rank, self.suit.clone())
+        deck + (self.suit as u8 * 13 + rank) as u16
     }
 }
 
-pub fn generate_deck() -> Vec<u8> {
-    return (1..53).collect::<Vec<u8>>();
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.joker {
+            return f.write_str(JOKER);
+        }
+
+        write!(f, "{}{}", self.rank.letter(), self.suit.letter())
+    }
+}
+
+// Notation for a joker
+const JOKER: &str = "Jk";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseCardError {
+    Empty,
+    InvalidRank(String),
+    InvalidSuit(String),
+    MissingSuit(String),
+}
+
+impl fmt::Display for ParseCardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseCardError::Empty => write!(f, "no card given"),
+            ParseCardError::InvalidRank(s) => write!(f, "invalid rank in \"{}\"", s),
+            ParseCardError::InvalidSuit(s) => write!(f, "invalid suit in \"{}\"", s),
+            ParseCardError::MissingSuit(s) => write!(f, "missing suit in \"{}\"", s),
+        }
+    }
+}
+
+impl Error for ParseCardError {}
+
+impl FromStr for Rank {
+    type Err = ParseCardError;
+
+    // Accepts the notation letter in either case, or "10" for the ten
+    fn from_str(s: &str) -> Result<Rank, ParseCardError> {
+        if s == "10" {
+            return Ok(Rank::Ten);
+        }
+
+        let mut chars = s.chars();
+        let rank = match (chars.next(), chars.next()) {
+            (Some(c), None) => Rank::iter().find(|r| r.letter() == c.to_ascii_uppercase()),
+            _ => None,
+        };
+
+        rank.ok_or_else(|| ParseCardError::InvalidRank(s.to_string()))
+    }
+}
+
+impl FromStr for Suit {
+    type Err = ParseCardError;
+
+    // Accepts the notation letter in either case or the suit symbol
+    fn from_str(s: &str) -> Result<Suit, ParseCardError> {
+        let mut chars = s.chars();
+        let suit = match (chars.next(), chars.next()) {
+            (Some(c), None) => Suit::iter()
+                .find(|suit| suit.letter() == c.to_ascii_lowercase() || suit.symbol() == c),
+            _ => None,
+        };
+
+        suit.ok_or_else(|| ParseCardError::InvalidSuit(s.to_string()))
+    }
+}
+
+impl FromStr for Card {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<Card, ParseCardError> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case(JOKER) {
+            return Ok(Card::joker(0));
+        }
+
+        let (rank, suit) = match s.char_indices().last() {
+            Some((0, _)) => return Err(ParseCardError::MissingSuit(s.to_string())),
+            Some((i, _)) => s.split_at(i),
+            None => return Err(ParseCardError::Empty),
+        };
+
+        let rank = rank.parse::<Rank>().map_err(|_| ParseCardError::InvalidRank(s.to_string()))?;
+        let suit = suit.parse::<Suit>().map_err(|_| ParseCardError::InvalidSuit(s.to_string()))?;
+
+        Ok(Card::new(rank, suit))
+    }
+}
+
+// Parses a run of cards in standard notation such as "AhKhQhJhTh".
+// Cards may also be separated by spaces or commas: "Ah 10d, 2c".
+pub fn parse_hand(s: &str) -> Result<Vec<Card>, ParseCardError> {
+    let mut cards = vec![];
+    let mut chars = s.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        if c.is_whitespace() || c == ',' {
+            continue;
+        }
+
+        // "10" is the only rank written with two characters
+        if c == '1' && matches!(chars.peek(), Some((_, '0'))) {
+            chars.next();
+        }
+
+        let end = match chars.next() {
+            Some((i, suit)) if !suit.is_whitespace() && suit != ',' => i + suit.len_utf8(),
+            _ => return Err(ParseCardError::MissingSuit(s[start..].trim().to_string())),
+        };
+
+        cards.push(s[start..end].parse()?);
+    }
+
+    Ok(cards)
 }
 
 #[cfg(test)]
@@ -52,85 +361,119 @@ mod tests {
     // first and last card of each
     #[test]
     fn aces() {
-        assert_eq!(
-            Card::new(1),
-            Card {
-                suit: "Spades".to_string(),
-                rank: 1,
-                value: 1,
-            }
-        );
-
-        assert_eq!(
-            Card::new(13),
-            Card {
-                suit: "Spades".to_string(),
-                rank: 13,
-                value: 13,
-            }
-        );
+        assert_eq!(Card::from_value(1), Some(Card::new(Rank::Ace, Suit::Spades)));
+        assert_eq!(Card::from_value(13), Some(Card::new(Rank::King, Suit::Spades)));
     }
 
     #[test]
     fn hearts() {
-        assert_eq!(
-            Card::new(14),
-            Card {
-                suit: "Hearts".to_string(),
-                rank: 1,
-                value: 14,
-            }
-        );
-
-        assert_eq!(
-            Card::new(26),
-            Card {
-                suit: "Hearts".to_string(),
-                rank: 13,
-                value: 26,
-            }
-        );
+        assert_eq!(Card::from_value(14), Some(Card::new(Rank::Ace, Suit::Hearts)));
+        assert_eq!(Card::from_value(26), Some(Card::new(Rank::King, Suit::Hearts)));
     }
 
     #[test]
     fn diamonds() {
-        assert_eq!(
-            Card::new(27),
-            Card {
-                suit: "Diamonds".to_string(),
-                rank: 1,
-                value: 27,
-            }
-        );
-
-        assert_eq!(
-            Card::new(39),
-            Card {
-                suit: "Diamonds".to_string(),
-                rank: 13,
-                value: 39,
-            }
-        );
+        assert_eq!(Card::from_value(27), Some(Card::new(Rank::Ace, Suit::Diamonds)));
+        assert_eq!(Card::from_value(39), Some(Card::new(Rank::King, Suit::Diamonds)));
     }
 
     #[test]
     fn clubs() {
-        assert_eq!(
-            Card::new(40),
-            Card {
-                suit: "Clubs".to_string(),
-                rank: 1,
-                value: 40,
-            }
-        );
-
-        assert_eq!(
-            Card::new(52),
-            Card {
-                suit: "Clubs".to_string(),
-                rank: 13,
-                value: 52,
-            }
-        );
+        assert_eq!(Card::from_value(40), Some(Card::new(Rank::Ace, Suit::Clubs)));
+        assert_eq!(Card::from_value(52), Some(Card::new(Rank::King, Suit::Clubs)));
+    }
+
+    #[test]
+    fn invalid_values() {
+        assert_eq!(Card::from_value(0), None);
+        assert_eq!(Card::from_value(449), None);
+        assert_eq!(Rank::from_value(1), None);
+        assert_eq!(Rank::from_value(15), None);
+    }
+
+    #[test]
+    fn value_round_trip() {
+        for v in 1..449 {
+            assert_eq!(v, Card::from_value(v).unwrap().value());
+        }
+    }
+
+    #[test]
+    fn decks() {
+        let ace = Card::new(Rank::Ace, Suit::Spades);
+        let second = ace.in_deck(1);
+
+        assert_ne!(ace, second);
+        assert_eq!(Some(second), Card::from_value(57));
+        assert_eq!(Some(Card::joker(0).in_deck(1)), Card::from_value(109));
+        assert_eq!(ace.to_string(), second.to_string());
+    }
+
+    #[test]
+    fn rank_order() {
+        assert!(Rank::Ace > Rank::King);
+        assert!(Rank::Two < Rank::Three);
+        assert_eq!(13, Rank::iter().count());
+        assert_eq!(4, Suit::iter().count());
+    }
+
+    #[test]
+    fn parse_card() {
+        assert_eq!(Ok(Card::new(Rank::Ace, Suit::Hearts)), "Ah".parse());
+        assert_eq!(Ok(Card::new(Rank::Ten, Suit::Diamonds)), "Td".parse());
+        assert_eq!(Ok(Card::new(Rank::Ten, Suit::Diamonds)), "10d".parse());
+        assert_eq!(Ok(Card::new(Rank::King, Suit::Spades)), "k♠".parse());
+        assert_eq!(Err(ParseCardError::Empty), "".parse::<Card>());
+        assert_eq!(Err(ParseCardError::MissingSuit("A".to_string())), "A".parse::<Card>());
+        assert_eq!(Err(ParseCardError::InvalidRank("1h".to_string())), "1h".parse::<Card>());
+        assert_eq!(Err(ParseCardError::InvalidSuit("Ax".to_string())), "Ax".parse::<Card>());
+    }
+
+    #[test]
+    fn format_card() {
+        for v in 1..53 {
+            let card = Card::from_value(v).unwrap();
+            assert_eq!(Ok(card), card.to_string().parse());
+        }
+
+        assert_eq!("Th", Card::new(Rank::Ten, Suit::Hearts).to_string());
+        assert_eq!("2c", Card::new(Rank::Two, Suit::Clubs).to_string());
+    }
+
+    #[test]
+    fn jokers() {
+        let joker = Card::joker(1);
+
+        assert!(joker.joker);
+        assert_ne!(Card::joker(0), joker);
+        assert_ne!(Card::new(Rank::Ace, Suit::Hearts), joker);
+        assert_eq!(Some(joker), Card::from_value(joker.value()));
+        assert_eq!("Jk", joker.to_string());
+        assert_eq!(Ok(Card::joker(0)), "JK".parse());
+        assert_eq!(Ok(vec![Card::joker(0), Card::new(Rank::Jack, Suit::Hearts)]), parse_hand("JkJh"));
+    }
+
+    #[test]
+    fn parse_hands() {
+        let royal = parse_hand("AhKhQhJhTh").unwrap();
+        assert_eq!(5, royal.len());
+        assert_eq!(Card::new(Rank::Ten, Suit::Hearts), royal[4]);
+
+        assert_eq!(royal, parse_hand("Ah Kh, Qh Jh 10h").unwrap());
+        assert_eq!(Ok(vec![]), parse_hand(""));
+        assert_eq!(Err(ParseCardError::MissingSuit("K".to_string())), parse_hand("AhK"));
+        assert_eq!(Err(ParseCardError::MissingSuit("A h".to_string())), parse_hand("A h"));
+        assert_eq!(Err(ParseCardError::InvalidSuit("Kx".to_string())), parse_hand("AhKx"));
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!("10", Rank::Ten.to_string());
+        assert_eq!('T', Rank::Ten.letter());
+        assert_eq!("A", Rank::Ace.to_string());
+        assert_eq!("Queen", Rank::Queen.name());
+        assert_eq!("Hearts", Suit::Hearts.to_string());
+        assert_eq!('♦', Suit::Diamonds.symbol());
+        assert_eq!('c', Suit::Clubs.letter());
     }
 }
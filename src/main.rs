@@ -1,10 +1,21 @@
+use single_player_poker::config::{self, Config};
+use std::env;
 use std::process;
 
-mod game;
 mod ui;
 
 fn main() {
-    if let Err(e) = ui::run() {
+    let config = Config::from_args(env::args().skip(1)).unwrap_or_else(|e| {
+        eprintln!("{}\n\n{}\n{}\n{}", e, config::USAGE, config::STRATEGY_OPTION, config::RULES_OPTIONS);
+        process::exit(2);
+    });
+
+    if config.show_help {
+        println!("{}\n{}\n{}", config::USAGE, config::STRATEGY_OPTION, config::RULES_OPTIONS);
+        return;
+    }
+
+    if let Err(e) = ui::run(config) {
         println!("Application error: {}", e);
 
         process::exit(1);
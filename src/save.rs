@@ -0,0 +1,297 @@
+use crate::game::paytable::{self, PayTable, PayTableError};
+use crate::game::rules::{Rules, Variant};
+use crate::game::state::Snapshot;
+use crate::storage::{self, StorageError};
+use crate::{Card, Rank, MAX_DECKS};
+use std::path::{Path, PathBuf};
+
+// Version written in new saves. Saves of this version and older can
+// be read.
+pub const SAVE_VERSION: u32 = 1;
+
+const FILE_NAME: &str = "save.txt";
+
+// A session saved when the game quits, as `key = value` lines like the
+// pay table files, with the pay table itself at the end:
+//
+//     version = 1
+//     seed = 1234
+//     deals = 7
+//     score = 42
+//     hands_played = 6
+//     variant = "standard"
+//     decks = 1
+//     jokers = 0
+//     wild_rank = "2"
+//     hand = 1 14 27 40 5
+//     draw_pile = 52 51 50 ...
+//     to_change = 0 4
+//     cursor = 2
+//
+//     [pay_table]
+//     name = "Standard"
+//     ...
+//
+// Cards are written as their numbers (see `Card::from_value`) so the
+// same card of two decks can be told apart. Versions only ever add
+// keys: a key missing from an older save takes its default value, and
+// only `version`, `seed` and `draw_pile` are required.
+pub fn default_path() -> Result<PathBuf, StorageError> {
+    Ok(storage::data_dir()?.join(FILE_NAME))
+}
+
+// The saved session, or None without one
+pub fn load(path: &Path) -> Result<Option<Snapshot>, StorageError> {
+    storage::read_file(path)?.map(|contents| parse(&contents)).transpose()
+}
+
+pub fn save(path: &Path, snapshot: &Snapshot) -> Result<(), StorageError> {
+    storage::write_file(path, &to_file_string(snapshot))
+}
+
+// Forgets the saved session, if there is one
+pub fn remove(path: &Path) -> Result<(), StorageError> {
+    storage::remove_file(path)
+}
+
+pub fn to_file_string(snapshot: &Snapshot) -> String {
+    let cards = |cards: &[Card]| {
+        cards.iter().map(|c| c.value().to_string()).collect::<Vec<_>>().join(" ")
+    };
+    let rules = &snapshot.rules;
+    let mut s = format!("version = {}\n", SAVE_VERSION);
+
+    s.push_str(&format!("seed = {}\n", snapshot.seed));
+    s.push_str(&format!("deals = {}\n", snapshot.deals));
+    s.push_str(&format!("score = {}\n", snapshot.score));
+    s.push_str(&format!("hands_played = {}\n", snapshot.hands_played));
+    s.push_str(&format!("variant = \"{}\"\n", variant_key(rules.variant)));
+    s.push_str(&format!("decks = {}\n", rules.decks));
+    s.push_str(&format!("jokers = {}\n", rules.jokers));
+    if let Some(rank) = rules.wild_rank {
+        s.push_str(&format!("wild_rank = \"{}\"\n", rank.letter()));
+    }
+    s.push_str(&format!("hand = {}\n", cards(&snapshot.hand)));
+    s.push_str(&format!("draw_pile = {}\n", cards(&snapshot.draw_pile)));
+    let to_change: Vec<String> = snapshot.to_change.iter().map(|i| i.to_string()).collect();
+    s.push_str(&format!("to_change = {}\n", to_change.join(" ")));
+    s.push_str(&format!("cursor = {}\n", snapshot.cursor));
+
+    s.push_str("\n[pay_table]\n");
+    s.push_str(&snapshot.pay_table.to_file_string());
+
+    s
+}
+
+pub fn parse(contents: &str) -> Result<Snapshot, StorageError> {
+    // The pay table section is read by the pay table parser
+    let (session, pay_table_line, pay_table) = match contents.find("[pay_table]") {
+        Some(start) => {
+            let line = contents[..start].lines().count() + 1;
+            (&contents[..start], line, Some(&contents[start..]))
+        }
+        None => (contents, 0, None),
+    };
+
+    let mut version = None;
+    let mut seed = None;
+    let mut draw_pile = None;
+    let mut snapshot = Snapshot {
+        rules: Rules::default(),
+        pay_table: PayTable::standard(),
+        seed: 0,
+        deals: 0,
+        score: 0,
+        hands_played: 0,
+        hand: vec![],
+        draw_pile: vec![],
+        to_change: vec![],
+        cursor: 0,
+    };
+
+    for (n, line) in session.lines().enumerate() {
+        let error = |message: String| StorageError::Parse { line: n + 1, message };
+        let line = paytable::strip_comment(line);
+        if line.is_empty() {
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| error(format!("expected `key = value`, got \"{}\"", line)))?;
+        let key = key.trim();
+        let value = value.trim().trim_matches('"');
+        let invalid = || error(format!("invalid {} \"{}\"", key, value));
+
+        match key {
+            "version" => {
+                let v: u32 = value.parse().map_err(|_| invalid())?;
+                if v == 0 || v > SAVE_VERSION {
+                    return Err(error(format!("unsupported save version {}", v)));
+                }
+                version = Some(v);
+            }
+            "seed" => seed = Some(value.parse().map_err(|_| invalid())?),
+            "deals" => snapshot.deals = value.parse().map_err(|_| invalid())?,
+            "score" => snapshot.score = value.parse().map_err(|_| invalid())?,
+            "hands_played" => snapshot.hands_played = value.parse().map_err(|_| invalid())?,
+            "variant" => {
+                snapshot.rules.variant = match value {
+                    "standard" => Variant::Standard,
+                    "short-deck" => Variant::ShortDeck,
+                    _ => return Err(invalid()),
+                }
+            }
+            // The same limits as the command line options
+            "decks" => {
+                snapshot.rules.decks = value
+                    .parse()
+                    .ok()
+                    .filter(|n| (1..=MAX_DECKS).contains(n))
+                    .ok_or_else(invalid)?
+            }
+            "jokers" => {
+                snapshot.rules.jokers = value.parse().ok().filter(|n| *n <= 4).ok_or_else(invalid)?
+            }
+            "wild_rank" => {
+                snapshot.rules.wild_rank = Some(value.parse::<Rank>().map_err(|_| invalid())?)
+            }
+            "hand" => snapshot.hand = parse_cards(value).ok_or_else(invalid)?,
+            "draw_pile" => draw_pile = Some(parse_cards(value).ok_or_else(invalid)?),
+            "to_change" => {
+                snapshot.to_change = value
+                    .split_whitespace()
+                    .map(|i| i.parse().ok())
+                    .collect::<Option<_>>()
+                    .ok_or_else(invalid)?
+            }
+            "cursor" => snapshot.cursor = value.parse().map_err(|_| invalid())?,
+            // Left by a newer release of the same version
+            _ => {}
+        }
+    }
+
+    let missing = |key: &str| StorageError::Parse {
+        line: session.lines().count(),
+        message: format!("missing {}", key),
+    };
+    version.ok_or_else(|| missing("version"))?;
+    snapshot.seed = seed.ok_or_else(|| missing("seed"))?;
+    snapshot.draw_pile = draw_pile.ok_or_else(|| missing("draw_pile"))?;
+
+    // The wild rank has to be in the deck, whichever key came first
+    if let Some(rank) = snapshot.rules.wild_rank {
+        if !snapshot.rules.ranks().contains(&rank) {
+            return Err(StorageError::Parse {
+                line: session.lines().count(),
+                message: format!("no {}s in the deck to be wild", rank.letter()),
+            });
+        }
+    }
+
+    if let Some(pay_table) = pay_table {
+        snapshot.pay_table = PayTable::parse(pay_table).map_err(|e| match e {
+            PayTableError::Parse { line, message } => StorageError::Parse {
+                line: line + pay_table_line - 1,
+                message,
+            },
+            e => StorageError::Io(e.to_string()),
+        })?;
+    }
+
+    Ok(snapshot)
+}
+
+fn variant_key(variant: Variant) -> &'static str {
+    match variant {
+        Variant::Standard => "standard",
+        Variant::ShortDeck => "short-deck",
+    }
+}
+
+fn parse_cards(s: &str) -> Option<Vec<Card>> {
+    s.split_whitespace()
+        .map(|v| v.parse().ok().and_then(Card::from_value))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::state::{Action, GameState};
+
+    fn snapshot() -> Snapshot {
+        let rules = Rules {
+            variant: Variant::ShortDeck,
+            decks: 2,
+            jokers: 1,
+            wild_rank: Some(Rank::Nine),
+        };
+        let state = GameState::new(rules, PayTable::short_deck(), 99);
+        let (state, _) = state.update(Action::Deal).unwrap();
+        let (state, _) = state.update(Action::Select(vec![3, 1])).unwrap();
+
+        state.snapshot()
+    }
+
+    #[test]
+    fn round_trip() {
+        let snapshot = snapshot();
+        let contents = to_file_string(&snapshot);
+
+        assert!(contents.starts_with("version = 1\nseed = 99\n"));
+        assert_eq!(Ok(snapshot), parse(&contents));
+    }
+
+    #[test]
+    fn older_saves() {
+        // Only the required keys, everything else takes its default
+        let snapshot = parse("version = 1\nseed = 5\ndraw_pile = 1 2 3\n").unwrap();
+
+        assert_eq!(5, snapshot.seed);
+        assert_eq!(Rules::default(), snapshot.rules);
+        assert_eq!(PayTable::standard(), snapshot.pay_table);
+        assert_eq!(3, snapshot.draw_pile.len());
+        assert!(snapshot.hand.is_empty());
+    }
+
+    #[test]
+    fn bad_saves() {
+        assert_eq!(
+            Err(StorageError::Parse {
+                line: 1,
+                message: "unsupported save version 2".to_string()
+            }),
+            parse("version = 2\nseed = 5\ndraw_pile = 1")
+        );
+        assert_eq!(
+            Err(StorageError::Parse {
+                line: 2,
+                message: "missing seed".to_string()
+            }),
+            parse("version = 1\ndraw_pile = 1")
+        );
+        assert_eq!(
+            Err(StorageError::Parse {
+                line: 3,
+                message: "invalid hand \"1 999\"".to_string()
+            }),
+            parse("version = 1\nseed = 5\nhand = 1 999\n")
+        );
+        assert_eq!(
+            Err(StorageError::Parse {
+                line: 3,
+                message: "invalid decks \"0\"".to_string()
+            }),
+            parse("version = 1\nseed = 5\ndecks = 0\ndraw_pile = 1")
+        );
+        assert!(parse("version = 1\nseed = 5\njokers = 9\ndraw_pile = 1").is_err());
+        assert!(parse("version = 1\nseed = 5\nwild_rank = \"2\"\nvariant = \"short-deck\"\ndraw_pile = 1").is_err());
+
+        // Pay table errors point at the line of the whole file
+        let mut contents = to_file_string(&snapshot());
+        contents.push_str("pair = lots\n");
+        let line = contents.lines().count();
+        assert!(matches!(parse(&contents), Err(StorageError::Parse { line: l, .. }) if l == line));
+    }
+}
@@ -0,0 +1,341 @@
+use crate::game::hand::{HandCategory, HandValue};
+use crate::game::paytable::{self, PayTable};
+use crate::game::rules::Rules;
+use crate::storage::{self, StorageError};
+use crate::Card;
+use std::path::{Path, PathBuf};
+
+const FILE_NAME: &str = "stats.txt";
+
+// Lifetime numbers of every hand played with one pay table and rules
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Stats {
+    pub pay_table: String,
+    // What the pay table pays (see `PayTable::id`), tables with the same
+    // name can pay differently
+    pub pay_table_id: String,
+    // Description of the rules, as shown on the welcome screen
+    pub rules: String,
+    pub hands: u64,
+    pub points: i64,
+    // Hands ending in each category, indexed by category
+    pub counts: [u64; HandCategory::ALL.len()],
+    // Cards of the best hand ever made
+    pub best: Option<Vec<Card>>,
+    // Hands in a row without any points, up to the last one
+    pub dry_streak: u64,
+    pub longest_dry_streak: u64,
+}
+
+impl Stats {
+    pub fn new(pay_table: &str, pay_table_id: &str, rules: &str) -> Stats {
+        Stats {
+            pay_table: pay_table.to_string(),
+            pay_table_id: pay_table_id.to_string(),
+            rules: rules.to_string(),
+            hands: 0,
+            points: 0,
+            counts: [0; HandCategory::ALL.len()],
+            best: None,
+            dry_streak: 0,
+            longest_dry_streak: 0,
+        }
+    }
+
+    // Adds a hand played with the rules the stats are kept for
+    pub fn record(&mut self, rules: &Rules, hand: &[Card], value: HandValue, points: i32) {
+        self.hands += 1;
+        self.points += points as i64;
+        self.counts[value.category() as usize] += 1;
+
+        if points > 0 {
+            self.dry_streak = 0;
+        } else {
+            self.dry_streak += 1;
+            self.longest_dry_streak = self.longest_dry_streak.max(self.dry_streak);
+        }
+
+        if self.best_value(rules).map_or(true, |best| value > best) {
+            self.best = Some(hand.to_vec());
+        }
+    }
+
+    // Value of the best hand under the rules
+    pub fn best_value(&self, rules: &Rules) -> Option<HandValue> {
+        let cards: &[Card; 5] = self.best.as_deref()?.try_into().ok()?;
+
+        Some(rules.evaluate(cards))
+    }
+
+    pub fn average_points(&self) -> f64 {
+        if self.hands == 0 {
+            return 0.0;
+        }
+
+        self.points as f64 / self.hands as f64
+    }
+
+    // Share of the hands ending in the category
+    pub fn frequency(&self, category: HandCategory) -> f64 {
+        if self.hands == 0 {
+            return 0.0;
+        }
+
+        self.counts[category as usize] as f64 / self.hands as f64
+    }
+}
+
+// Stats of every pay table and rules played, kept in a text file of
+// TOML-like tables, one per pay table and rules:
+//
+//     [[stats]]
+//     pay_table = "Standard"
+//     pay_table_id = "67516319911d8963"
+//     rules = "No wild cards"
+//     hands = 120
+//     points = 98
+//     counts = 60 40 10 5 2 2 1 0 0 0 0 0
+//     best = 1 13 12 11 10
+//     dry_streak = 2
+//     longest_dry_streak = 11
+//
+// The counts are in the order of the categories from high card to
+// royal flush, cards are written as their numbers (see
+// `Card::from_value`). Tables without a `pay_table_id`, from before it
+// was kept, belong to the preset of their pay table name.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LifetimeStats {
+    tables: Vec<Stats>,
+}
+
+impl LifetimeStats {
+    pub fn default_path() -> Result<PathBuf, StorageError> {
+        Ok(storage::data_dir()?.join(FILE_NAME))
+    }
+
+    // No stats yet if the file doesn't exist
+    pub fn load(path: &Path) -> Result<LifetimeStats, StorageError> {
+        match storage::read_file(path)? {
+            Some(contents) => LifetimeStats::parse(&contents),
+            None => Ok(LifetimeStats::default()),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), StorageError> {
+        storage::write_file(path, &self.to_file_string())
+    }
+
+    pub fn parse(contents: &str) -> Result<LifetimeStats, StorageError> {
+        let mut stats = LifetimeStats::default();
+
+        for (n, line) in contents.lines().enumerate() {
+            let error = |message: String| StorageError::Parse { line: n + 1, message };
+            let line = paytable::strip_comment(line);
+            if line.is_empty() {
+                continue;
+            }
+
+            if line == "[[stats]]" {
+                stats.tables.push(Stats::new("", "", ""));
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| error(format!("expected `key = value`, got \"{}\"", line)))?;
+            let key = key.trim();
+            let value = value.trim().trim_matches('"');
+            let invalid = || error(format!("invalid {} \"{}\"", key, value));
+            let table = stats
+                .tables
+                .last_mut()
+                .ok_or_else(|| error("expected [[stats]] first".to_string()))?;
+
+            match key {
+                "pay_table" => table.pay_table = value.to_string(),
+                "pay_table_id" => table.pay_table_id = value.to_string(),
+                "rules" => table.rules = value.to_string(),
+                "hands" => table.hands = value.parse().map_err(|_| invalid())?,
+                "points" => table.points = value.parse().map_err(|_| invalid())?,
+                "counts" => {
+                    let counts: Vec<u64> = value
+                        .split_whitespace()
+                        .map(|c| c.parse().ok())
+                        .collect::<Option<_>>()
+                        .ok_or_else(invalid)?;
+                    table.counts = counts.try_into().map_err(|_| invalid())?;
+                }
+                "best" => {
+                    let cards = value
+                        .split_whitespace()
+                        .map(|v| v.parse().ok().and_then(Card::from_value))
+                        .collect::<Option<Vec<Card>>>()
+                        .filter(|cards| cards.len() == 5)
+                        .ok_or_else(invalid)?;
+                    table.best = Some(cards);
+                }
+                "dry_streak" => table.dry_streak = value.parse().map_err(|_| invalid())?,
+                "longest_dry_streak" => {
+                    table.longest_dry_streak = value.parse().map_err(|_| invalid())?
+                }
+                // Left by a newer release
+                _ => {}
+            }
+        }
+
+        for table in stats.tables.iter_mut().filter(|t| t.pay_table_id.is_empty()) {
+            table.pay_table_id = PayTable::preset_id(&table.pay_table).unwrap_or_default();
+        }
+
+        Ok(stats)
+    }
+
+    pub fn to_file_string(&self) -> String {
+        let mut s = String::new();
+
+        for (i, table) in self.tables.iter().enumerate() {
+            if i > 0 {
+                s.push('\n');
+            }
+
+            let counts: Vec<String> = table.counts.iter().map(|c| c.to_string()).collect();
+            s.push_str("[[stats]]\n");
+            s.push_str(&format!("pay_table = \"{}\"\n", table.pay_table));
+            s.push_str(&format!("pay_table_id = \"{}\"\n", table.pay_table_id));
+            s.push_str(&format!("rules = \"{}\"\n", table.rules));
+            s.push_str(&format!("hands = {}\n", table.hands));
+            s.push_str(&format!("points = {}\n", table.points));
+            s.push_str(&format!("counts = {}\n", counts.join(" ")));
+            if let Some(best) = &table.best {
+                let cards: Vec<String> = best.iter().map(|c| c.value().to_string()).collect();
+                s.push_str(&format!("best = {}\n", cards.join(" ")));
+            }
+            s.push_str(&format!("dry_streak = {}\n", table.dry_streak));
+            s.push_str(&format!("longest_dry_streak = {}\n", table.longest_dry_streak));
+        }
+
+        s
+    }
+
+    pub fn get(&self, pay_table: &PayTable, rules: &Rules) -> Option<&Stats> {
+        let id = pay_table.id();
+        let rules = rules.to_string();
+
+        self.tables
+            .iter()
+            .find(|t| t.pay_table_id == id && t.rules == rules)
+    }
+
+    // Adds a hand to the stats of its pay table and rules
+    pub fn record(&mut self, pay_table: &PayTable, rules: &Rules, hand: &[Card], value: HandValue) {
+        let id = pay_table.id();
+        let description = rules.to_string();
+        let index = match self
+            .tables
+            .iter()
+            .position(|t| t.pay_table_id == id && t.rules == description)
+        {
+            Some(i) => i,
+            None => {
+                self.tables.push(Stats::new(&pay_table.name, &id, &description));
+                self.tables.len() - 1
+            }
+        };
+
+        self.tables[index].record(rules, hand, value, pay_table.points(value));
+    }
+
+    pub fn tables(&self) -> &[Stats] {
+        &self.tables
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_hand;
+
+    fn record(stats: &mut LifetimeStats, table: &PayTable, rules: &Rules, s: &str) {
+        let hand = parse_hand(s).unwrap();
+        let value = rules.evaluate(hand[..].try_into().unwrap());
+        stats.record(table, rules, &hand, value);
+    }
+
+    #[test]
+    fn records() {
+        let rules = Rules::default();
+        let table = PayTable::jacks_or_better();
+        let mut stats = LifetimeStats::default();
+        assert_eq!(None, stats.get(&table, &rules));
+
+        record(&mut stats, &table, &rules, "2c 7d 9h Js Kc");
+        record(&mut stats, &table, &rules, "Ts Th 4c 8d Ac");
+        record(&mut stats, &table, &rules, "Ks Kh 4c 8d Ac");
+        record(&mut stats, &table, &rules, "2c 3c 9c Jc Kc");
+        record(&mut stats, &table, &rules, "2c 7d 9h Js Kc");
+
+        let s = stats.get(&table, &rules).unwrap();
+        assert_eq!(5, s.hands);
+        assert_eq!(7, s.points);
+        assert_eq!(1.4, s.average_points());
+        assert_eq!(0.4, s.frequency(HandCategory::HighCard));
+        assert_eq!(0.4, s.frequency(HandCategory::Pair));
+        assert_eq!(HandCategory::Flush, s.best_value(&rules).unwrap().category());
+        // The low pair doesn't pay so the first two hands are dry
+        assert_eq!(2, s.longest_dry_streak);
+        assert_eq!(1, s.dry_streak);
+
+        // Other pay tables and rules are kept apart, even with the same name
+        record(&mut stats, &PayTable::standard(), &rules, "2c 7d 9h Js Kc");
+        let mut renamed = PayTable::standard();
+        renamed.name = table.name.clone();
+        record(&mut stats, &renamed, &rules, "2c 7d 9h Js Kc");
+        assert_eq!(3, stats.tables().len());
+        assert_eq!(5, stats.get(&table, &rules).unwrap().hands);
+    }
+
+    #[test]
+    fn file_round_trip() {
+        let deuces = Rules {
+            wild_rank: Some(crate::Rank::Two),
+            ..Rules::default()
+        };
+        let mut stats = LifetimeStats::default();
+        let mut bonus = PayTable::standard();
+        bonus.name = "Bonus #2".to_string();
+        record(&mut stats, &bonus, &Rules::default(), "Ts Th 4c 8d Ac");
+        record(&mut stats, &PayTable::deuces_wild(), &deuces, "2s 2h 4c 8d Ac");
+        record(&mut stats, &PayTable::deuces_wild(), &deuces, "2c 3d 9h Js Kc");
+
+        let contents = stats.to_file_string();
+        assert_eq!(Ok(stats), LifetimeStats::parse(&contents));
+    }
+
+    #[test]
+    fn older_files() {
+        let stats = LifetimeStats::parse("[[stats]]\npay_table = \"Standard\"\nrules = \"No wild cards\"\nhands = 3").unwrap();
+
+        assert_eq!(3, stats.get(&PayTable::standard(), &Rules::default()).unwrap().hands);
+    }
+
+    #[test]
+    fn newer_files() {
+        let stats = LifetimeStats::parse("[[stats]]\npay_table = \"Standard\"\nrules = \"No wild cards\"\nluck = 7\nhands = 3").unwrap();
+
+        assert_eq!(3, stats.get(&PayTable::standard(), &Rules::default()).unwrap().hands);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(LifetimeStats::parse("hands = 3").is_err());
+        assert!(LifetimeStats::parse("[[stats]]\ncounts = 1 2 3").is_err());
+        assert!(LifetimeStats::parse("[[stats]]\nbest = 1 2").is_err());
+        assert_eq!(
+            Err(StorageError::Parse {
+                line: 2,
+                message: "invalid hands \"lots\"".to_string()
+            }),
+            LifetimeStats::parse("[[stats]]\nhands = lots")
+        );
+    }
+}
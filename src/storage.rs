@@ -0,0 +1,150 @@
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+// Name of the folder of the game in the user data directory
+const APP_DIR: &str = "single_player_poker";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StorageError {
+    // No data directory could be found for the user
+    NoDataDir,
+    Io(String),
+    Parse { line: usize, message: String },
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StorageError::NoDataDir => f.write_str("Couldn't find a data directory to save to"),
+            StorageError::Io(e) => write!(f, "Couldn't read or write game data: {}", e),
+            StorageError::Parse { line, message } => {
+                write!(f, "Game data error on line {}: {}", line, message)
+            }
+        }
+    }
+}
+
+impl Error for StorageError {}
+
+impl From<io::Error> for StorageError {
+    fn from(e: io::Error) -> StorageError {
+        StorageError::Io(e.to_string())
+    }
+}
+
+// Where the game keeps its files: $XDG_DATA_HOME/single_player_poker,
+// falling back to ~/.local/share/single_player_poker, or
+// %APPDATA%\single_player_poker on Windows
+pub fn data_dir() -> Result<PathBuf, StorageError> {
+    let base = env::var_os("XDG_DATA_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("share")))
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from))
+        .ok_or(StorageError::NoDataDir)?;
+
+    Ok(base.join(APP_DIR))
+}
+
+// Contents of the file, or None when it doesn't exist yet
+pub fn read_file(path: &Path) -> Result<Option<String>, StorageError> {
+    match fs::read_to_string(path) {
+        Ok(contents) => Ok(Some(contents)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+// Writes the whole file, creating its directory first. The contents go
+// to a temporary file that then replaces the old one, so a crash never
+// leaves half a file behind.
+pub fn write_file(path: &Path, contents: &str) -> Result<(), StorageError> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let temp = path.with_extension("tmp");
+    fs::write(&temp, contents)?;
+    fs::rename(&temp, path)?;
+
+    Ok(())
+}
+
+// Adds to the end of the file, creating it and its directory first
+pub fn append_file(path: &Path, contents: &str) -> Result<(), StorageError> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(contents.as_bytes())?;
+
+    Ok(())
+}
+
+// Deletes the file if it exists
+pub fn remove_file(path: &Path) -> Result<(), StorageError> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::daily::{DailyResult, DailyResults};
+    use crate::date::Date;
+
+    // A directory of the test's own, so test runs at the same time
+    // don't share files
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("spp_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        dir
+    }
+
+    #[test]
+    fn files() {
+        let dir = temp_dir("files");
+        let path = dir.join("nested").join("data.txt");
+
+        assert_eq!(Ok(None), read_file(&path));
+
+        write_file(&path, "first").unwrap();
+        write_file(&path, "second").unwrap();
+        assert_eq!(Ok(Some("second".to_string())), read_file(&path));
+        assert!(!path.with_extension("tmp").exists());
+
+        append_file(&path, " and third").unwrap();
+        assert_eq!(Ok(Some("second and third".to_string())), read_file(&path));
+
+        remove_file(&path).unwrap();
+        assert_eq!(Ok(None), read_file(&path));
+        assert_eq!(Ok(()), remove_file(&path));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    // The formats only go through their parsers in their own tests, one
+    // of them makes the trip through a real file here
+    #[test]
+    fn format_through_a_file() {
+        let dir = temp_dir("format");
+        let path = dir.join("daily.txt");
+        assert_eq!(Ok(DailyResults::default()), DailyResults::load(&path));
+
+        let mut results = DailyResults::default();
+        let date = Date::new(2024, 3, 9).unwrap();
+        results.record(DailyResult { date, score: 42, hands: 10 });
+        results.save(&path).unwrap();
+        assert_eq!(Ok(results), DailyResults::load(&path));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,15 +1,31 @@
 use crossterm::{
-    event::{self, Event as CEvent, KeyCode},
+    event::{self, Event as CEvent, KeyCode, KeyEvent},
     terminal::{disable_raw_mode, enable_raw_mode},
 };
-use single_player_poker::Card;
-use crate::game::poker as poker;
-use std::process;
+use single_player_poker::game::advisor::{self, Outcomes};
+use single_player_poker::game::error::PokerError;
+use single_player_poker::config::Config;
+use single_player_poker::daily::{self, DailyResult, DailyResults, DAILY_HANDS};
+use single_player_poker::date::Date;
+use single_player_poker::game::hand::HandCategory;
+use single_player_poker::game::paytable::PayTable;
+use single_player_poker::game::rules::Rules;
+use single_player_poker::game::strategy;
+use single_player_poker::game::trainer::{Decision, Trainer};
+use single_player_poker::highscores::{self, HighScore, HighScores, Mode};
+use single_player_poker::history::{self, HandRecord};
+use single_player_poker::game::state::{Action, GameEvent, GameState, Snapshot};
+use single_player_poker::save;
+use single_player_poker::stats::{LifetimeStats, Stats};
+use single_player_poker::storage::StorageError;
+use single_player_poker::{Card, Rank};
+use rand::{thread_rng, Rng};
+use std::fmt;
 use std::sync::mpsc;
 use std::thread;
 use std::time::{Duration, Instant};
 use tui::{
-    backend::CrosstermBackend,
+    backend::{Backend, CrosstermBackend},
     layout::{Alignment, Constraint, Direction, Layout},
     style::{Color, Modifier, Style},
     text::{Span, Spans},
@@ -26,23 +42,62 @@ use std::io;
 enum Event<I> {
     Input(I),
     Tick,
+    // Deal counts of each hand category for the pay table odds
+    Counts(Rules, [u64; HandCategory::ALL.len()]),
+    // Every choice of cards to change for the hand with the id
+    Advice(u64, Result<Vec<Outcomes>, PokerError>),
+    // Draw odds of the selected cards of the hand with the id
+    DrawOdds(u64, Result<Outcomes, PokerError>),
 }
 
+// A hand in play handed to a worker thread. Working it out can take
+// seconds with many decks or wild cards, the id tells its results
+// apart from those of later hands.
+struct HandJob {
+    id: u64,
+    hand: Vec<Card>,
+    remaining: Vec<Card>,
+    rules: Rules,
+    pay_table: PayTable,
+}
+
+impl HandJob {
+    fn new(id: u64, state: &GameState) -> HandJob {
+        HandJob {
+            id,
+            hand: state.hand().to_vec(),
+            remaining: state.remaining().to_vec(),
+            rules: *state.rules(),
+            pay_table: state.pay_table().clone(),
+        }
+    }
+}
+
+// Ticks between two steps of autoplay
+const AUTOPLAY_TICKS: u32 = 3;
+
 #[derive(Copy, Clone, Debug, PartialEq)]
 enum Screen {
     Welcome,
     Game,
+    // End of a training session
+    Summary,
+    // End of a game, the player enters a name for the high scores
+    GameOver,
+    // Lifetime statistics of the pay table and rules
+    Stats,
 }
 
-pub fn run() -> Result<(), Box<dyn std::error::Error>> {
+pub fn run(config: Config) -> Result<(), Box<dyn std::error::Error>> {
     // stdin won't be printed and input isn't buffered
-    enable_raw_mode().expect("Can run in raw mode");
+    enable_raw_mode()?;
 
     // mpsc channel to communicate between input handler and renderer
     let (tx, rx) = mpsc::channel();
     let tick_rate = Duration::from_millis(200);
 
     // input loop
+    let input_tx = tx.clone();
     thread::spawn(move || {
        let mut last_tick = Instant::now();
        loop {
@@ -52,14 +107,12 @@ pub fn run() -> Result<(), Box<dyn std::error::Error>> {
 
            if event::poll(timeout).expect("Poll works") {
                if let CEvent::Key(key) = event::read().expect("Can read events") {
-                   tx.send(Event::Input(key)).expect("Can send events");
+                   input_tx.send(Event::Input(key)).expect("Can send events");
                }
            }
 
-           if last_tick.elapsed() >= tick_rate {
-               if let Ok(_) = tx.send(Event::Tick) {
-                   last_tick = Instant::now();
-               }
+           if last_tick.elapsed() >= tick_rate && input_tx.send(Event::Tick).is_ok() {
+               last_tick = Instant::now();
            }
        }
     });
@@ -70,175 +123,655 @@ pub fn run() -> Result<(), Box<dyn std::error::Error>> {
     let mut terminal = Terminal::new(backend)?;
     terminal.clear()?;
 
+    // The terminal is restored even if the game stops on an error
+    let result = run_game(&mut terminal, &tx, &rx, &config);
+
+    disable_raw_mode()?;
+    terminal.show_cursor()?;
+    terminal.clear()?;
+
+    result
+}
+
+fn run_game<B: Backend>(
+    terminal: &mut Terminal<B>,
+    tx: &mpsc::Sender<Event<KeyEvent>>,
+    rx: &mpsc::Receiver<Event<KeyEvent>>,
+    config: &Config,
+) -> Result<(), Box<dyn std::error::Error>> {
     let mut active_screen = Screen::Welcome;
-    let mut game_active = false;
-    let mut deck: Vec<u8> = poker::generate_deck();
-    let mut hand: Vec<Card> = vec![];
-    let mut to_change: Vec<usize> = vec![];
-    let mut discarded: Vec<u8> = vec![];
-    let mut score = 0;
-    let mut points = 0;
     let mut help_toggle = false;
-    let _poker_hand: &str;
-    
+    let mut error: Option<PokerError> = None;
+    let mut counts = None;
+    let mut advisor_toggle = false;
+    let mut advice: Option<Vec<Outcomes>> = None;
+    // Id of the hand the advice was asked for
+    let mut advice_asked: Option<u64> = None;
+    let mut trainer = Trainer::new();
+    let mut decision: Option<Decision> = None;
+    // The player drew before the advice the grade comes from was in
+    let mut grade_waiting = false;
+    let mut draw_odds: Option<Outcomes> = None;
+    // Hand id and selection the odds were last asked for
+    let mut draw_odds_asked: Option<(u64, Vec<usize>)> = None;
+    let odds_tx = spawn_draw_odds_worker(tx);
+    // Goes up with every hand shown, so results of other hands are dropped
+    let mut hand_id: u64 = 0;
+    spawn_counts(tx, config.rules);
+    // Problems reading or writing saved results, kept apart from the
+    // game errors which clear on the next key
+    let mut storage_error: Option<StorageError> = None;
+    // The daily challenge is played once, what was recorded today
+    // blocks it
+    let today = Date::today();
+    let mut daily_results = DailyResults::default();
+    let mut daily_blocked = false;
+    if config.daily {
+        match DailyResults::default_path().and_then(|path| DailyResults::load(&path)) {
+            Ok(results) => {
+                daily_blocked = results.get(today).is_some();
+                daily_results = results;
+            }
+            // Without the results there's no telling if it was played
+            Err(e) => {
+                daily_blocked = true;
+                storage_error = Some(e);
+            }
+        }
+    }
+    // The session left when the game last quit can be picked up again,
+    // apart from daily challenges
+    let mut saved = None;
+    if !config.daily {
+        match save::default_path().and_then(|path| save::load(&path)) {
+            Ok(snapshot) => saved = snapshot,
+            Err(e) => storage_error = Some(e),
+        }
+    }
+    let mut high_scores = HighScores::default();
+    // Scores that couldn't be read are left in their file
+    let mut high_scores_loaded = false;
+    match HighScores::default_path().and_then(|path| HighScores::load(&path)) {
+        Ok(scores) => {
+            high_scores = scores;
+            high_scores_loaded = true;
+        }
+        Err(e) => storage_error = Some(e),
+    }
+    let mut lifetime_stats = LifetimeStats::default();
+    // A file that couldn't be read isn't written over, the player's
+    // stats are still in it
+    let mut lifetime_stats_loaded = false;
+    match LifetimeStats::default_path().and_then(|path| LifetimeStats::load(&path)) {
+        Ok(stats) => {
+            lifetime_stats = stats;
+            lifetime_stats_loaded = true;
+        }
+        Err(e) => storage_error = Some(e),
+    }
+    // Where the stats screen goes back to
+    let mut stats_from = Screen::Welcome;
+    // Name for the high scores, kept from one game to the next
+    let mut player_name = String::new();
+    // Every shuffle comes from the seed so the session can be replayed,
+    // the daily one comes from the date
+    let new_game = || {
+        let seed = match config.daily {
+            true => daily::seed(today),
+            false => config.seed.unwrap_or_else(|| thread_rng().gen()),
+        };
+
+        GameState::new(config.rules, config.pay_table.clone(), seed)
+    };
+    // The game itself, the rest is how it's shown
+    let mut state = new_game();
+    // The name was checked with the other options
+    let new_strategy = |state: &GameState| {
+        strategy::by_name(&config.strategy, state.pay_table(), state.seed().wrapping_add(1))
+            .expect("known strategy")
+    };
+    let mut strategy = new_strategy(&state);
+    let mut autoplay = false;
+    // Games the strategy played a part in have their own high scores
+    let mut autoplay_used = false;
+    let mut autoplay_ticks = 0;
+    let mut autoplay_chosen = false;
+
     // Stateful list where cards will be stored
     let mut hand_list_state = ListState::default();
-    hand_list_state.select(Some(0));
 
     // Render loop
     loop {
+        // A resumed session brings its own rules
+        let rules = &state.rules().clone();
+        let pay_table = &state.pay_table().clone();
+        let seed = state.seed();
+        let game_active = state.in_play();
+
+        // The odds follow the cards selected to change
+        let mut selected = state.to_change().to_vec();
+        selected.sort_unstable();
+        let asked = Some((hand_id, selected.clone()));
+        if game_active && draw_odds_asked != asked {
+            draw_odds_asked = asked;
+            let _ = odds_tx.send((HandJob::new(hand_id, &state), selected.clone()));
+        }
+
+        // Every choice is worked out once per hand, in the background.
+        // The trainer grades the player's choice against them.
+        if (advisor_toggle || config.trainer) && game_active && advice_asked != Some(hand_id) {
+            advice_asked = Some(hand_id);
+            spawn_advice(tx, HandJob::new(hand_id, &state));
+        }
+
+        hand_list_state.select(Some(state.cursor()));
+
         // Terminal is separated vertically into 3 sections
         // header, body, and footer
         terminal.draw(|rect| {
             let size = rect.size();
-            let help_size = if !help_toggle { 5 } else { 9 };
+            let help_size = if !help_toggle { 8 } else { 9 };
             let constraints = match active_screen {
-                Screen::Welcome => vec![Constraint::Min(20)],
+                Screen::Welcome | Screen::Summary | Screen::GameOver | Screen::Stats => {
+                    vec![Constraint::Min(20), Constraint::Length(1)]
+                }
                 Screen::Game => {
-                    vec![
+                    let mut constraints = vec![
                         Constraint::Length(4),
                         Constraint::Min(4),
                         Constraint::Length(5),
-                        Constraint::Length(help_size),
-                    ]
+                    ];
+                    if config.trainer {
+                        constraints.push(Constraint::Length(4));
+                    }
+                    constraints.push(Constraint::Length(help_size));
+                    constraints
                 }
             };
 
             let chunks = Layout::default()
                 .direction(Direction::Vertical)
                 .margin(2)
-                .constraints(constraints.as_ref())
+                .constraints::<&[Constraint]>(constraints.as_ref())
                 .split(size);
 
             match active_screen {
                 Screen::Welcome => {
-                    rect.render_widget(render_welcome(), chunks[0]);
+                    let mut status = vec![match config.daily {
+                        true => daily_status(today, daily_results.get(today).filter(|_| daily_blocked)),
+                        false => format!("Seed: {}", seed),
+                    }];
+                    status.push("Press s for your lifetime statistics".to_string());
+                    let prompt = if daily_blocked {
+                        "♠ Press q to quit ♠"
+                    } else if let Some(snapshot) = &saved {
+                        status.push(saved_status(snapshot));
+                        "♠ Press c to continue, Enter for a new game ♠"
+                    } else {
+                        "♠ Press Enter to play ♠"
+                    };
+                    let mode = session_mode(config, false);
+                    let scores = high_score_lines(&high_scores, mode, pay_table, rules);
+                    let welcome = render_welcome(pay_table, rules, status, scores, prompt);
+                    rect.render_widget(welcome, chunks[0]);
+
+                    if let Some(e) = &error {
+                        rect.render_widget(render_error(e), chunks[1]);
+                    } else if let Some(e) = &storage_error {
+                        rect.render_widget(render_error(e), chunks[1]);
+                    }
                 },
                 Screen::Game => {
-                    let help = render_help(&help_toggle);
-                    let score = render_score(score);
+                    let help = render_help(&help_toggle, config.daily);
+                    let title = if config.daily {
+                        let hand_number = state.hands_played() + game_active as u32;
+                        format!("Daily {}, hand {}/{}", today, hand_number, DAILY_HANDS)
+                    } else if autoplay {
+                        format!("Seed {}, autoplay: {}", seed, strategy.name())
+                    } else {
+                        format!("Seed {}", seed)
+                    };
+                    let score_widget = render_score(state.score(), title);
 
                     let poker_chunks = Layout::default()
                         .direction(Direction::Horizontal)
                         .constraints(
                             [
-                            Constraint::Percentage(40), 
-                            Constraint::Percentage(60)
+                            Constraint::Percentage(30),
+                            Constraint::Percentage(30),
+                            Constraint::Percentage(40)
                             ]
                             .as_ref(),
                         )
                         .split(chunks[1]);
 
-                    let game = render_game(&mut hand, &to_change);
-                    let selected_card = hand_list_state.selected()
-                        .unwrap_or_else(|| {
-                            eprintln!("Problem getting selected card");
-                            process::exit(1);
-                    });
-                    let (rank, suit) = &hand[selected_card].get_card();
-                    let ascii_card = render_ascii_card(rank, suit);
+                    let result = state.result();
+                    let won = result.map(|r| r.value.category());
+                    rect.render_widget(render_pay_table(pay_table, rules, counts.as_ref(), won), poker_chunks[2]);
 
-                    rect.render_stateful_widget(game, poker_chunks[0],
+                    let game = render_game(state.hand(), state.to_change());
+                    let selected_card = state.hand().get(state.cursor());
+
+                    match &advice {
+                        Some(choices) if advisor_toggle && game_active => {
+                            let advice = render_advice(state.hand(), choices, state.to_change());
+                            rect.render_widget(advice, poker_chunks[1]);
+                        }
+                        None if advisor_toggle && game_active => {
+                            rect.render_widget(render_working("Expected points"), poker_chunks[1]);
+                        }
+                        _ => {
+                            if let Some(card) = selected_card {
+                                rect.render_widget(render_ascii_card(card), poker_chunks[1]);
+                            }
+                        }
+                    }
+
+                    // The hand is listed above the odds of the draw
+                    let hand_chunks = Layout::default()
+                        .direction(Direction::Vertical)
+                        .constraints([Constraint::Length(7), Constraint::Min(0)].as_ref())
+                        .split(poker_chunks[0]);
+
+                    rect.render_stateful_widget(game, hand_chunks[0],
                                                 &mut hand_list_state);
-                    rect.render_widget(ascii_card, poker_chunks[1]);
 
-                    if !game_active {
-                        let (message, _poker_hand) = render_message(&points);
+                    match &draw_odds {
+                        Some(outcomes) if game_active && outcomes.to_change == selected => {
+                            rect.render_widget(render_draw_odds(outcomes, rules), hand_chunks[1]);
+                        }
+                        _ if game_active => {
+                            rect.render_widget(render_working("After the draw"), hand_chunks[1]);
+                        }
+                        _ => {}
+                    }
+
+                    let daily_over = config.daily && state.hands_played() >= DAILY_HANDS;
+                    if let Some(e) = &error {
+                        rect.render_widget(render_error(e), chunks[2]);
+                    } else if let Some(e) = &storage_error {
+                        rect.render_widget(render_error(e), chunks[2]);
+                    } else if let Some(result) = result {
+                        let category = result.value.category();
+                        let message = match daily_over {
+                            true => render_daily_end(category, result.points, state.score()),
+                            false => render_message(category, result.points).0,
+                        };
                         rect.render_widget(message, chunks[2]);
                     }
 
-                    rect.render_widget(score, chunks[0]);
-                    rect.render_widget(help, chunks[3]);
+                    if config.trainer && grade_waiting {
+                        rect.render_widget(render_working("Trainer"), chunks[3]);
+                    } else if config.trainer {
+                        rect.render_widget(render_grade(decision.as_ref(), &trainer), chunks[3]);
+                    }
+
+                    rect.render_widget(score_widget, chunks[0]);
+                    rect.render_widget(help, chunks[chunks.len() - 1]);
+                },
+                Screen::Summary => {
+                    rect.render_widget(render_summary(&trainer), chunks[0]);
+                },
+                Screen::GameOver => {
+                    let mode = session_mode(config, autoplay_used);
+                    rect.render_widget(render_game_over(&state, mode, &player_name), chunks[0]);
+                },
+                Screen::Stats => {
+                    let stats = lifetime_stats.get(pay_table, rules);
+                    rect.render_widget(render_stats(stats, pay_table, rules, counts.as_ref()), chunks[0]);
                 },
             }
         })?;
 
-        match rx.recv()? {
-            Event::Input(event) => match event.code {
-                KeyCode::Char('q') => {
-                    disable_raw_mode()?;
-                    terminal.show_cursor()?;
-                    terminal.clear()?;
-                    break;
+        let key = match rx.recv()? {
+            Event::Input(event) => Some(event.code),
+            // The strategy plays a step every few ticks: it deals,
+            // then selects the cards to change, then draws
+            Event::Tick if autoplay && matches!(active_screen, Screen::Welcome | Screen::Game) => {
+                autoplay_ticks += 1;
+
+                if autoplay_ticks < AUTOPLAY_TICKS {
+                    None
+                } else if game_active && !autoplay_chosen {
+                    autoplay_ticks = 0;
+                    autoplay_chosen = true;
+                    let choice = strategy.choose(state.hand(), rules);
+                    apply(&mut state, Action::Select(choice), &mut error);
+                    None
+                } else {
+                    autoplay_ticks = 0;
+                    autoplay_chosen = false;
+                    Some(KeyCode::Enter)
                 }
-                KeyCode::Down => {
-                    if let Some(selected) = hand_list_state.selected() {
-                        hand_list_state.select(Some((selected + 1) % 5))
+            }
+            Event::Tick => None,
+            // Counts for the rules of another session are too late
+            Event::Counts(counts_rules, c) => {
+                if &counts_rules == rules {
+                    counts = Some(c);
+                }
+                None
+            }
+            Event::DrawOdds(id, result) => {
+                if id == hand_id {
+                    match result {
+                        Ok(outcomes) => draw_odds = Some(outcomes),
+                        Err(e) => error = Some(e),
                     }
-                },
-                KeyCode::Up => {
-                    if let Some(selected) = hand_list_state.selected() {
-                        if selected > 0 {
-                            hand_list_state.select(Some(selected - 1));
-                        } else {
-                            hand_list_state.select(Some(4));
+                }
+                None
+            }
+            Event::Advice(id, result) => {
+                if id != hand_id {
+                    None
+                } else {
+                    match result {
+                        Ok(choices) => advice = Some(choices),
+                        Err(e) => error = Some(e),
+                    }
+
+                    // The draw waiting for its grade goes on
+                    match std::mem::take(&mut grade_waiting) && advice.is_some() {
+                        true => Some(KeyCode::Enter),
+                        false => None,
+                    }
+                }
+            }
+        };
+
+        let Some(code) = key else {
+            continue;
+        };
+
+        // Keys the game doesn't know are left to the view
+        let action = match code {
+            // Every key types the name, Enter records the score and Esc
+            // leaves without it
+            _ if active_screen == Screen::GameOver => {
+                match code {
+                    KeyCode::Enter | KeyCode::Esc => {
+                        if code == KeyCode::Enter {
+                            let entry = HighScore {
+                                name: player_name.clone(),
+                                date: today,
+                                mode: session_mode(config, autoplay_used),
+                                pay_table: pay_table.name.clone(),
+                                pay_table_id: pay_table.id(),
+                                rules: rules.to_string(),
+                                hands: state.hands_played(),
+                                score: state.score(),
+                            };
+                            high_scores.record(entry);
+                            if high_scores_loaded {
+                                storage_error = HighScores::default_path()
+                                    .and_then(|path| high_scores.save(&path))
+                                    .err();
+                            }
                         }
+
+                        // The game is over so there's nothing to continue
+                        if config.daily {
+                            daily_blocked = true;
+                        } else if let Err(e) = save::default_path().and_then(|path| save::remove(&path)) {
+                            storage_error = Some(e);
+                        }
+
+                        state = new_game();
+                        if state.rules() != rules {
+                            counts = None;
+                            spawn_counts(tx, *state.rules());
+                        }
+                        strategy = new_strategy(&state);
+                        saved = None;
+                        autoplay_used = false;
+                        trainer = Trainer::new();
+                        decision = None;
+                        active_screen = Screen::Welcome;
                     }
-                },
-                KeyCode::Enter => {
-                    if !game_active {
+                    KeyCode::Backspace => {
+                        player_name.pop();
+                    }
+                    KeyCode::Char(c) if player_name.chars().count() < highscores::MAX_NAME_LEN => {
+                        player_name.push(c);
+                    }
+                    _ => {}
+                }
+                None
+            },
+            KeyCode::Char('q') => {
+                // Training sessions end on their summary
+                if config.trainer && active_screen == Screen::Game && !trainer.decisions().is_empty() {
+                    active_screen = Screen::Summary;
+                    None
+                } else {
+                    Some(Action::Quit)
+                }
+            },
+            _ if active_screen == Screen::Summary => None,
+            KeyCode::Char('s') | KeyCode::Esc if active_screen == Screen::Stats => {
+                active_screen = stats_from;
+                None
+            },
+            _ if active_screen == Screen::Stats => None,
+            KeyCode::Char('s') => {
+                stats_from = active_screen;
+                active_screen = Screen::Stats;
+                None
+            },
+            KeyCode::Char('c') if active_screen == Screen::Welcome && saved.is_some() => {
+                error = None;
+
+                match GameState::resume(saved.take().expect("saved game")) {
+                    Ok(resumed) => {
+                        if resumed.rules() != rules {
+                            counts = None;
+                            spawn_counts(tx, *resumed.rules());
+                        }
+                        state = resumed;
+                        strategy = new_strategy(&state);
                         active_screen = Screen::Game;
-                        game_active = true;
-                        hand = poker::deal(&mut deck)
-                    } else {
-                        if to_change.len() > 0 {
-                            discarded = poker::change_cards(&mut deck,
-                                                            &mut hand, 
-                                                            &to_change);
-                            to_change.clear();
+                        hand_id += 1;
+                        advice = None;
+                        draw_odds = None;
+
+                        // Between hands it goes on with the next one
+                        match state.in_play() {
+                            true => None,
+                            false => Some(Action::Deal),
                         }
-                        points = poker::check_hand(&hand);
-                        score += points;
-                        game_active = false;
-                        poker::reset_deck(&mut deck, &mut hand, &mut discarded);
+                    }
+                    Err(e) => {
+                        error = Some(e);
+                        None
+                    }
+                }
+            },
+            KeyCode::Down => Some(Action::MoveCursor(1)),
+            KeyCode::Up => Some(Action::MoveCursor(-1)),
+            KeyCode::Enter if !game_active => {
+                error = None;
 
+                // The last hand of the daily challenge ends the game
+                let daily_done = state.hands_played() >= DAILY_HANDS;
+                if config.daily && daily_done && active_screen == Screen::Game {
+                    active_screen = Screen::GameOver;
+                    continue;
+                }
+
+                // Each day's challenge is dealt once
+                let daily_over = daily_blocked || state.hands_played() >= DAILY_HANDS;
+                if config.daily && daily_over {
+                    continue;
+                }
+
+                // Starting the challenge is recorded before the first
+                // cards show so quitting doesn't allow another go
+                if config.daily && state.hands_played() == 0 {
+                    let started = DailyResult { date: today, score: 0, hands: 0 };
+                    if let Err(e) = save_daily(&mut daily_results, started) {
+                        storage_error = Some(e);
+                        continue;
                     }
-                },
-                KeyCode::Char(' ') => {
-                    if game_active {
-                        let selection = hand_list_state.selected()
-                            .unwrap_or_else(|| {
-                                eprintln!("Problem getting selected card");
-                                process::exit(1);
-                            });
-
-                        if to_change.contains(&selection) {
-                            to_change.retain(|i| i != &selection);
-                        } else {
-                            if to_change.len() == 3 {
-                                to_change.pop();
-                            }
-                            to_change.push(selection);
+                }
+
+                Some(Action::Deal)
+            },
+            KeyCode::Enter => {
+                error = None;
+
+                // The choice is graded before the new cards are drawn,
+                // against the advice for the hand. The hand stays in
+                // play if anything goes wrong so the player can fix the
+                // selection.
+                if config.trainer {
+                    let Some(choices) = &advice else {
+                        grade_waiting = true;
+                        continue;
+                    };
+                    match trainer.grade(state.hand(), state.to_change(), choices) {
+                        Ok(d) => {
+                            decision = Some(d.clone());
+                            Some(Action::Draw)
+                        }
+                        Err(e) => {
+                            error = Some(e);
+                            None
                         }
                     }
-                },
-                KeyCode::Char('h') => {
-                    help_toggle = !help_toggle;
+                } else {
+                    Some(Action::Draw)
+                }
+            },
+            KeyCode::Char(' ') if game_active => {
+                error = None;
+                Some(Action::ToggleCard)
+            },
+            // The daily challenge ends on its own after the last hand
+            KeyCode::Char('e') if active_screen == Screen::Game && !config.daily => {
+                if state.hands_played() > 0 {
+                    active_screen = Screen::GameOver;
+                    autoplay = false;
+                }
+                None
+            }
+            KeyCode::Char('h') => {
+                help_toggle = !help_toggle;
+                None
+            }
+            // The daily challenge has to be played by hand, without hints
+            KeyCode::Char('a') if !config.daily => {
+                advisor_toggle = !advisor_toggle;
+                None
+            }
+            KeyCode::Char('p') if !config.daily => {
+                autoplay = !autoplay;
+                autoplay_used |= autoplay;
+                autoplay_ticks = 0;
+                autoplay_chosen = false;
+                None
+            }
+            _ => None,
+        };
+
+        let events = match action {
+            Some(action) => apply(&mut state, action, &mut error),
+            None => vec![],
+        };
+
+        let mut quit = false;
+        for event in events {
+            match event {
+                GameEvent::Dealt(_) => {
+                    active_screen = Screen::Game;
+                    hand_id += 1;
+                    advice = None;
+                    draw_odds = None;
+                    grade_waiting = false;
                 }
-                _ => {},
+                GameEvent::Drawn(result) => {
+                    lifetime_stats.record(pay_table, rules, state.hand(), result.value);
+                    if lifetime_stats_loaded {
+                        storage_error = LifetimeStats::default_path()
+                            .and_then(|path| lifetime_stats.save(&path))
+                            .err();
+                    }
+
+                    let record = HandRecord::new(
+                        state.hands_played(),
+                        today,
+                        &pay_table.name,
+                        &rules.to_string(),
+                        state.seed(),
+                        state.hand(),
+                        &result,
+                        state.score(),
+                    );
+                    storage_error = history::default_path()
+                        .and_then(|path| history::append(&path, &record))
+                        .err()
+                        .or(storage_error);
+
+                    if config.daily {
+                        let result = DailyResult {
+                            date: today,
+                            score: state.score(),
+                            hands: state.hands_played(),
+                        };
+                        storage_error = save_daily(&mut daily_results, result).err().or(storage_error);
+                    }
+                }
+                // The session is kept for next time once it's started
+                GameEvent::Quit => {
+                    if !config.daily && state.deals() > 0 {
+                        save::save(&save::default_path()?, &state.snapshot())?;
+                    }
+                    quit = true;
+                }
+                _ => {}
             }
-            Event::Tick => {},
+        }
+
+        if quit {
+            break;
+        }
+
+        // Autoplay stops on anything unexpected so the error stays on
+        // screen
+        if error.is_some() {
+            autoplay = false;
         }
     }
 
     Ok(())
 }
 
+// Moves the game on with the action. A failed action leaves the game
+// as it was and shows the error.
+fn apply(state: &mut GameState, action: Action, error: &mut Option<PokerError>) -> Vec<GameEvent> {
+    match state.update(action) {
+        Ok((next, events)) => {
+            *state = next;
+            events
+        }
+        Err(e) => {
+            *error = Some(e);
+            vec![]
+        }
+    }
+}
+
 // Rendering functions
-fn render_ascii_card<'a>(rank: &String, suit: &String) -> Paragraph<'a> {
-    let suit_symbol = match &suit[..] {
-        "Spades" => "♠",
-        "Hearts" => "♥",
-        "Diamonds" => "♦",
-        "Clubs" => "♣",
-        _ => panic!("Error"),
+fn render_ascii_card<'a>(card: &Card) -> Paragraph<'a> {
+    let (rank, suit_symbol) = if card.joker {
+        ("JK".to_string(), '★')
+    } else {
+        (card.rank.to_string(), card.suit.symbol())
     };
 
     let top;
-    let mid;
     let bot;
 
-    if rank == "10" {
+    if rank.len() == 2 {
         top = format!("│{rank}               │");
         bot = format!("│               {rank}│");
     } else {
@@ -246,7 +779,7 @@ fn render_ascii_card<'a>(rank: &String, suit: &String) -> Paragraph<'a> {
         bot = format!("│                {rank}│");
     }
 
-    mid = format!("│        {suit_symbol}        │");
+    let mid = format!("│        {suit_symbol}        │");
 
     let card = Paragraph::new(vec![
         Spans::from(vec![Span::raw("╭─────────────────╮")]),
@@ -276,8 +809,39 @@ fn render_ascii_card<'a>(rank: &String, suit: &String) -> Paragraph<'a> {
     card
 }
 
-fn render_game<'a>(hand: &mut Vec<Card>,
-    to_change: &Vec<usize>) -> List<'a> {
+// Counts the hands of the rules in the background, counting every
+// possible hand takes a moment. The odds show up once it's finished.
+fn spawn_counts(tx: &mpsc::Sender<Event<KeyEvent>>, rules: Rules) {
+    let tx = tx.clone();
+    thread::spawn(move || {
+        let _ = tx.send(Event::Counts(rules, rules.deal_counts()));
+    });
+}
+
+// Records the daily result and writes every result back to the file
+fn save_daily(results: &mut DailyResults, result: DailyResult) -> Result<(), StorageError> {
+    results.record(result);
+    results.save(&DailyResults::default_path()?)
+}
+
+fn render_error<'a>(error: &dyn fmt::Display) -> Paragraph<'a> {
+    Paragraph::new(vec![
+        Spans::from(vec![Span::styled(
+            error.to_string(),
+            Style::default()
+                .fg(Color::Red)
+                .add_modifier(Modifier::BOLD)
+        )]),
+    ])
+    .alignment(Alignment::Center)
+    .wrap(Wrap { trim: true })
+    .block(
+        Block::default()
+    )
+}
+
+fn render_game<'a>(hand: &[Card],
+    to_change: &[usize]) -> List<'a> {
 
     // Game block
     let game = Block::default()
@@ -286,9 +850,12 @@ fn render_game<'a>(hand: &mut Vec<Card>,
         .border_type(BorderType::Plain);
 
     let mut strings: Vec<String> = vec![];
-    for i in 0..5 {
-        let (rank, suit) = hand[i].get_card();
-        let mut string = format!("{rank} of {suit}");
+    for (i, card) in hand.iter().enumerate() {
+        let mut string = if card.joker {
+            "Joker".to_string()
+        } else {
+            format!("{} of {}", card.rank, card.suit)
+        };
         if to_change.contains(&i) {
             string.push('*');
         }
@@ -315,7 +882,238 @@ fn render_game<'a>(hand: &mut Vec<Card>,
     list
 }
 
-fn render_help<'a>(toggle: &bool) -> Paragraph<'a> {
+// Hand with the cards to change hidden, such as "Ah Kh -- -- Qh"
+fn choice_string(hand: &[Card], to_change: &[usize]) -> String {
+    hand.iter()
+        .enumerate()
+        .map(|(i, card)| match to_change.contains(&i) {
+            true => "--".to_string(),
+            false => card.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Ranks every choice for the hand in the background, the advice shows
+// up once it's finished
+fn spawn_advice(tx: &mpsc::Sender<Event<KeyEvent>>, job: HandJob) {
+    let tx = tx.clone();
+    thread::spawn(move || {
+        let choices = advisor::rank_choices(&job.hand, &job.remaining, &job.rules, &job.pay_table);
+        let _ = tx.send(Event::Advice(job.id, choices));
+    });
+}
+
+// Works out the draw odds of each selection sent to it, in the order
+// they come. Selections the player already changed are skipped, only
+// the latest one is worked out.
+fn spawn_draw_odds_worker(tx: &mpsc::Sender<Event<KeyEvent>>) -> mpsc::Sender<(HandJob, Vec<usize>)> {
+    let tx = tx.clone();
+    let (odds_tx, odds_rx) = mpsc::channel::<(HandJob, Vec<usize>)>();
+
+    thread::spawn(move || {
+        while let Ok(mut request) = odds_rx.recv() {
+            while let Ok(newer) = odds_rx.try_recv() {
+                request = newer;
+            }
+
+            let (job, to_change) = request;
+            let outcomes = advisor::outcomes(&job.hand, &to_change, &job.remaining, &job.rules, &job.pay_table);
+            if tx.send(Event::DrawOdds(job.id, outcomes)).is_err() {
+                return;
+            }
+        }
+    });
+
+    odds_tx
+}
+
+// Panel shown while its numbers are worked out
+fn render_working(title: &str) -> Paragraph<'_> {
+    Paragraph::new(vec![Spans::from(vec![Span::raw("…")])])
+        .alignment(Alignment::Left)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::White))
+                .title(title)
+                .border_type(BorderType::Plain),
+        )
+}
+
+// Every choice of cards to change with its expected points, the
+// current selection is highlighted
+fn render_advice<'a>(hand: &[Card], choices: &[Outcomes], to_change: &[usize]) -> Paragraph<'a> {
+    let mut selected = to_change.to_vec();
+    selected.sort_unstable();
+
+    let rows: Vec<Spans> = choices
+        .iter()
+        .map(|choice| {
+            let row = format!(
+                "{:<16}{:>9.4}",
+                choice_string(hand, &choice.to_change),
+                choice.expected()
+            );
+            let style = if choice.to_change == selected {
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+
+            Spans::from(vec![Span::styled(row, style)])
+        })
+        .collect();
+
+    Paragraph::new(rows)
+        .alignment(Alignment::Left)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::White))
+                .title("Expected points")
+                .border_type(BorderType::Plain),
+        )
+}
+
+// Rows of the draw odds panel, from the best hand down: the chance
+// of ending with each hand that can come up with the rules
+fn draw_odds_rows(outcomes: &Outcomes, rules: &Rules) -> Vec<(HandCategory, String)> {
+    rules
+        .categories()
+        .into_iter()
+        .rev()
+        .filter(|c| rules.possible(*c))
+        .map(|c| (c, format!("{:<17}{:>7.2}%", c.name(), outcomes.probability(c) * 100.0)))
+        .collect()
+}
+
+fn render_draw_odds<'a>(outcomes: &Outcomes, rules: &Rules) -> Paragraph<'a> {
+    let mut rows: Vec<Spans> = draw_odds_rows(outcomes, rules)
+        .into_iter()
+        .map(|(category, row)| {
+            let style = if outcomes.counts[category as usize] == 0 {
+                Style::default().fg(Color::DarkGray)
+            } else {
+                Style::default()
+            };
+
+            Spans::from(vec![Span::styled(row, style)])
+        })
+        .collect();
+
+    rows.push(Spans::from(vec![Span::raw("")]));
+    rows.push(Spans::from(vec![Span::styled(
+        format!("{:<17}{:>8.4}", "Expected points", outcomes.expected()),
+        Style::default().fg(Color::Cyan),
+    )]));
+
+    Paragraph::new(rows)
+        .alignment(Alignment::Left)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::White))
+                .title("After the draw")
+                .border_type(BorderType::Plain),
+        )
+}
+
+// How the last choice compares with the best one
+fn render_grade<'a>(decision: Option<&Decision>, trainer: &Trainer) -> Paragraph<'a> {
+    let grade = match decision {
+        Some(d) if d.is_correct() => Spans::from(vec![Span::styled(
+            format!("Best choice! {} is worth {:.4}", choice_string(&d.hand, &d.to_change), d.expected),
+            Style::default().fg(Color::Green),
+        )]),
+        Some(d) => Spans::from(vec![Span::styled(
+            format!(
+                "Cost {:.4}: {} is worth {:.4}, {} is worth {:.4}",
+                d.cost(),
+                choice_string(&d.hand, &d.to_change),
+                d.expected,
+                choice_string(&d.hand, &d.best),
+                d.best_expected
+            ),
+            Style::default().fg(Color::Red),
+        )]),
+        None => Spans::from(vec![Span::raw("Your choices will be graded here")]),
+    };
+
+    Paragraph::new(vec![
+        grade,
+        Spans::from(vec![Span::raw(format!(
+            "Accuracy: {}/{} ({:.1}%)",
+            trainer.correct(),
+            trainer.decisions().len(),
+            trainer.accuracy() * 100.0
+        ))]),
+    ])
+    .alignment(Alignment::Left)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .style(Style::default().fg(Color::White))
+            .title("Trainer")
+            .border_type(BorderType::Rounded),
+    )
+}
+
+// Accuracy of the training session and the choices that cost the most
+fn render_summary<'a>(trainer: &Trainer) -> Paragraph<'a> {
+    let mut lines = vec![
+        Spans::from(vec![Span::styled(
+            "Training summary",
+            Style::default().fg(Color::Cyan),
+        )]),
+        Spans::from(vec![Span::raw("")]),
+        Spans::from(vec![Span::raw(format!("Choices graded: {}", trainer.decisions().len()))]),
+        Spans::from(vec![Span::raw(format!(
+            "Best choices: {} ({:.1}%)",
+            trainer.correct(),
+            trainer.accuracy() * 100.0
+        ))]),
+        Spans::from(vec![Span::raw(format!("Expected points lost: {:.4}", trainer.total_cost()))]),
+        Spans::from(vec![Span::raw("")]),
+    ];
+
+    let worst = trainer.worst(5);
+    if worst.is_empty() {
+        lines.push(Spans::from(vec![Span::raw("No mistakes, well played!")]));
+    } else {
+        lines.push(Spans::from(vec![Span::raw("Worst mistakes")]));
+    }
+
+    for d in worst {
+        lines.push(Spans::from(vec![Span::raw("")]));
+        lines.push(Spans::from(vec![Span::styled(
+            format!("{}  cost {:.4}", choice_string(&d.hand, &[]), d.cost()),
+            Style::default().add_modifier(Modifier::BOLD),
+        )]));
+        lines.push(Spans::from(vec![Span::raw(format!(
+            "yours {} {:.4}  best {} {:.4}",
+            choice_string(&d.hand, &d.to_change),
+            d.expected,
+            choice_string(&d.hand, &d.best),
+            d.best_expected
+        ))]));
+    }
+
+    lines.push(Spans::from(vec![Span::raw("")]));
+    lines.push(Spans::from(vec![Span::raw("Press 'q' to quit")]));
+
+    Paragraph::new(lines)
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::White))
+                .border_type(BorderType::Plain),
+        )
+}
+
+// The daily challenge leaves out the keys it doesn't take
+fn render_help<'a>(toggle: &bool, daily: bool) -> Paragraph<'a> {
     let help;
 
     if *toggle {
@@ -341,11 +1139,17 @@ fn render_help<'a>(toggle: &bool) -> Paragraph<'a> {
         return help;
     }
 
-    help = Paragraph::new(vec![
-        Spans::from(vec![Span::raw("Press 'h' to toggle instructions on how to play.")]),
-        Spans::from(vec![Span::raw("")]),
-        Spans::from(vec![Span::raw("Press 'q' to quit.")]),
-    ])
+    let mut lines = vec!["Press 'h' to toggle instructions on how to play."];
+    if !daily {
+        lines.push("Press 'a' to toggle the advisor.");
+        lines.push("Press 'p' to let the strategy play on its own.");
+        lines.push("Press 'e' to end the game and record your score.");
+    }
+    lines.push("Press 's' for your lifetime statistics.");
+    lines.push("Press 'q' to quit.");
+    let lines: Vec<Spans> = lines.into_iter().map(|l| Spans::from(vec![Span::raw(l)])).collect();
+
+    help = Paragraph::new(lines)
     .alignment(Alignment::Left)
     .wrap(Wrap { trim: true })
     .block(
@@ -359,21 +1163,23 @@ fn render_help<'a>(toggle: &bool) -> Paragraph<'a> {
     help
 }
 
-fn render_message<'a>(points: &i32) -> (Paragraph<'a>, &str) {
-    let poker_hand = match points {
-        1 => "Pair!",
-        3 => "Two Pair!",
-        5 => "Three of a kind!",
-        10 => "Straight!",
-        15 => "Flush!",
-        18 => "Full House!",
-        20 => "Four of a kind!",
-        30 => "Straight Flush!",
-        40 => "Royal Flush!",
-        _ => "Nothing!"
+fn render_message<'a>(category: HandCategory, points: i32) -> (Paragraph<'a>, &'a str) {
+    let poker_hand = match category {
+        HandCategory::Pair => "Pair!",
+        HandCategory::TwoPair => "Two Pair!",
+        HandCategory::ThreeOfAKind => "Three of a kind!",
+        HandCategory::Straight => "Straight!",
+        HandCategory::Flush => "Flush!",
+        HandCategory::FullHouse => "Full House!",
+        HandCategory::FourOfAKind => "Four of a kind!",
+        HandCategory::StraightFlush => "Straight Flush!",
+        HandCategory::FiveOfAKind => "Five of a kind!",
+        HandCategory::WildRoyalFlush => "Wild Royal Flush!",
+        HandCategory::RoyalFlush => "Royal Flush!",
+        HandCategory::HighCard => "Nothing!"
     };
 
-    let points_added = format!("+{}", points.to_string());
+    let points_added = format!("+{}", points);
 
     let message = Paragraph::new(vec![
         Spans::from(vec![Span::styled(
@@ -397,7 +1203,76 @@ fn render_message<'a>(points: &i32) -> (Paragraph<'a>, &str) {
     (message, poker_hand)
 }
 
-fn render_score<'a>(s: i32) -> Paragraph<'a> {
+// Rows of the pay table panel, from the best hand down as ranked by
+// the rules. Hands that don't pay or can't come up with the rules are
+// left out. With the deal counts each row also shows the odds of
+// being dealt that hand.
+fn pay_table_rows(
+    pay_table: &PayTable,
+    rules: &Rules,
+    counts: Option<&[u64; HandCategory::ALL.len()]>,
+) -> Vec<(HandCategory, String)> {
+    rules
+        .categories()
+        .into_iter()
+        .rev()
+        .filter(|c| pay_table.payout(*c) > 0 && rules.possible(*c))
+        .map(|c| {
+            let name = match c {
+                HandCategory::Pair if pay_table.min_pair > Rank::Two => {
+                    format!("{}s or Better", pay_table.min_pair.name())
+                }
+                _ => c.name().to_string(),
+            };
+            let mut row = format!("{:<18}{:>5}", name, pay_table.payout(c));
+
+            if let Some(counts) = counts {
+                let total: u64 = counts.iter().sum();
+                let odds = match counts[c as usize] {
+                    0 => "-".to_string(),
+                    n => format!("1/{}", (total as f64 / n as f64).round()),
+                };
+                row.push_str(&format!("{:>11}", odds));
+            }
+
+            (c, row)
+        })
+        .collect()
+}
+
+fn render_pay_table<'a>(
+    pay_table: &PayTable,
+    rules: &Rules,
+    counts: Option<&[u64; HandCategory::ALL.len()]>,
+    won: Option<HandCategory>,
+) -> Paragraph<'a> {
+    let rows: Vec<Spans> = pay_table_rows(pay_table, rules, counts)
+        .into_iter()
+        .map(|(category, row)| {
+            let style = if Some(category) == won {
+                Style::default()
+                    .fg(Color::Green)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+
+            Spans::from(vec![Span::styled(row, style)])
+        })
+        .collect();
+
+    Paragraph::new(rows)
+        .alignment(Alignment::Left)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::White))
+                .title(pay_table.name.clone())
+                .border_type(BorderType::Plain),
+        )
+}
+
+fn render_score<'a>(s: i32, title: String) -> Paragraph<'a> {
     let score = Paragraph::new(vec![
         Spans::from(vec![Span::raw("Score")]),
         Spans::from(vec![Span::styled(
@@ -410,14 +1285,253 @@ fn render_score<'a>(s: i32) -> Paragraph<'a> {
         Block::default()
         .borders(Borders::ALL)
         .style(Style::default().fg(Color::White))
+        .title(title)
         .border_type(BorderType::Rounded),
     );
 
     score
 }
 
-fn render_welcome<'a>() -> Paragraph<'a> {
-    let welcome = Paragraph::new(vec![
+// Line of the welcome screen about the daily challenge, with the
+// result when it was played already
+fn daily_status(today: Date, played: Option<&DailyResult>) -> String {
+    match played {
+        Some(result) if result.is_finished() => format!(
+            "Daily challenge {} done: {} points, come back tomorrow",
+            today, result.score
+        ),
+        Some(result) => format!(
+            "Daily challenge {} stopped after {} of {} hands with {} points",
+            today, result.hands, DAILY_HANDS, result.score
+        ),
+        None => format!("Daily challenge {}: {} hands", today, DAILY_HANDS),
+    }
+}
+
+fn render_daily_end<'a>(category: HandCategory, points: i32, score: i32) -> Paragraph<'a> {
+    let (_, poker_hand) = render_message(category, points);
+
+    Paragraph::new(vec![
+        Spans::from(vec![Span::styled(
+            format!("{} +{}", poker_hand, points),
+            Style::default()
+                .fg(Color::Green)
+                .add_modifier(Modifier::BOLD)
+        )]),
+        Spans::from(vec![Span::raw("")]),
+        Spans::from(vec![Span::raw(format!("Daily challenge complete, final score: {}", score))]),
+        Spans::from(vec![Span::raw("Press enter to record your score")]),
+    ])
+    .alignment(Alignment::Center)
+    .block(
+        Block::default()
+    )
+}
+
+// Line of the welcome screen about the session that can be continued
+fn saved_status(snapshot: &Snapshot) -> String {
+    let hands = match snapshot.hands_played {
+        1 => "1 hand".to_string(),
+        n => format!("{} hands", n),
+    };
+    let hand = match snapshot.hand.is_empty() {
+        true => "",
+        false => ", hand in play",
+    };
+
+    format!(
+        "Saved game: {} points after {}{} ({})",
+        snapshot.score, hands, hand, snapshot.pay_table.name
+    )
+}
+
+// High score tables of the pay table and rules, the mode being played
+// first. Modes without scores are left out.
+fn high_score_lines(
+    scores: &HighScores,
+    mode: Mode,
+    pay_table: &PayTable,
+    rules: &Rules,
+) -> Vec<String> {
+    let rules = rules.to_string();
+    let modes = std::iter::once(mode).chain(Mode::ALL.into_iter().filter(|m| *m != mode));
+    let mut lines = vec![];
+
+    for mode in modes {
+        let top = scores.top(mode, &pay_table.id(), &rules, 3);
+        if top.is_empty() {
+            continue;
+        }
+
+        lines.push(format!("High scores, {}", mode));
+        for (i, e) in top.iter().enumerate() {
+            lines.push(format!("{}. {} {} in {} hands, {}", i + 1, e.name, e.score, e.hands, e.date));
+        }
+    }
+
+    lines
+}
+
+// How the session counts for the high scores
+fn session_mode(config: &Config, autoplay_used: bool) -> Mode {
+    if autoplay_used {
+        Mode::Autoplay
+    } else if config.daily {
+        Mode::Daily
+    } else if config.trainer {
+        Mode::Trainer
+    } else {
+        Mode::Classic
+    }
+}
+
+fn render_game_over<'a>(state: &GameState, mode: Mode, name: &str) -> Paragraph<'a> {
+    Paragraph::new(vec![
+        Spans::from(vec![Span::styled(
+            "Game over",
+            Style::default().fg(Color::Cyan),
+        )]),
+        Spans::from(vec![Span::raw("")]),
+        Spans::from(vec![Span::raw(format!(
+            "Final score: {} in {} hands ({})",
+            state.score(),
+            state.hands_played(),
+            mode
+        ))]),
+        Spans::from(vec![Span::raw(format!("{}, {}", state.pay_table().name, state.rules()))]),
+        Spans::from(vec![Span::raw("")]),
+        Spans::from(vec![
+            Span::raw("Your name: "),
+            Span::styled(
+                format!("{}_", name),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+        ]),
+        Spans::from(vec![Span::raw("")]),
+        Spans::from(vec![Span::raw("Press enter to record your score, esc to skip")]),
+    ])
+    .alignment(Alignment::Center)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .style(Style::default().fg(Color::White))
+            .border_type(BorderType::Plain),
+    )
+}
+
+// Lines above the stats table
+fn stats_summary(stats: &Stats, rules: &Rules) -> Vec<String> {
+    let best = match (&stats.best, stats.best_value(rules)) {
+        (Some(cards), Some(value)) => {
+            let cards: Vec<String> = cards.iter().map(|c| c.to_string()).collect();
+            format!("{} ({})", value.category().name(), cards.join(" "))
+        }
+        _ => "-".to_string(),
+    };
+
+    vec![
+        format!("Hands played: {}", stats.hands),
+        format!("Average points per hand: {:.3}", stats.average_points()),
+        format!("Best hand: {}", best),
+        format!(
+            "Longest dry streak: {} (current: {})",
+            stats.longest_dry_streak, stats.dry_streak
+        ),
+    ]
+}
+
+// Rows of the stats table, from the best hand down like the pay table:
+// how many hands ended in each category and how often, next to how
+// often it's dealt with the rules when the deal counts are known
+fn stats_rows(
+    stats: &Stats,
+    rules: &Rules,
+    counts: Option<&[u64; HandCategory::ALL.len()]>,
+) -> Vec<(HandCategory, String)> {
+    let total: u64 = counts.map(|c| c.iter().sum()).unwrap_or(0);
+
+    rules
+        .categories()
+        .into_iter()
+        .rev()
+        .filter(|c| rules.possible(*c))
+        .map(|c| {
+            let dealt = match counts {
+                Some(counts) if total > 0 => {
+                    format!("{:.4}%", counts[c as usize] as f64 * 100.0 / total as f64)
+                }
+                _ => "-".to_string(),
+            };
+            let row = format!(
+                "{:<18}{:>8}{:>10.2}%{:>12}",
+                c.name(),
+                stats.counts[c as usize],
+                stats.frequency(c) * 100.0,
+                dealt
+            );
+
+            (c, row)
+        })
+        .collect()
+}
+
+fn render_stats<'a>(
+    stats: Option<&Stats>,
+    pay_table: &PayTable,
+    rules: &Rules,
+    counts: Option<&[u64; HandCategory::ALL.len()]>,
+) -> Paragraph<'a> {
+    let mut lines = vec![
+        Spans::from(vec![Span::styled(
+            "Lifetime statistics",
+            Style::default().fg(Color::Cyan),
+        )]),
+        Spans::from(vec![Span::raw(format!("{}, {}", pay_table.name, rules))]),
+        Spans::from(vec![Span::raw("")]),
+    ];
+
+    match stats {
+        Some(stats) => {
+            lines.extend(
+                stats_summary(stats, rules)
+                    .into_iter()
+                    .map(|line| Spans::from(vec![Span::raw(line)])),
+            );
+            lines.push(Spans::from(vec![Span::raw("")]));
+            lines.push(Spans::from(vec![Span::styled(
+                format!("{:<18}{:>8}{:>11}{:>12}", "Hand", "Count", "Share", "Dealt"),
+                Style::default().add_modifier(Modifier::BOLD),
+            )]));
+            lines.extend(
+                stats_rows(stats, rules, counts)
+                    .into_iter()
+                    .map(|(_, row)| Spans::from(vec![Span::raw(row)])),
+            );
+        }
+        None => lines.push(Spans::from(vec![Span::raw("No hands played with these rules yet")])),
+    }
+
+    lines.push(Spans::from(vec![Span::raw("")]));
+    lines.push(Spans::from(vec![Span::raw("Press 's' to go back")]));
+
+    Paragraph::new(lines)
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::White))
+                .border_type(BorderType::Plain),
+        )
+}
+
+fn render_welcome<'a>(
+    pay_table: &PayTable,
+    rules: &Rules,
+    status: Vec<String>,
+    high_scores: Vec<String>,
+    prompt: &'a str,
+) -> Paragraph<'a> {
+    let mut lines = vec![
         Spans::from(vec![Span::raw("Welcome")]),
         Spans::from(vec![Span::raw("")]),
         Spans::from(vec![Span::raw("to")]),
@@ -444,14 +1558,27 @@ fn render_welcome<'a>() -> Paragraph<'a> {
         Spans::from(vec![Span::raw("╰─────────────────╯")]),
         Spans::from(vec![Span::raw("")]),
         Spans::from(vec![Span::raw("")]),
-        Spans::from(vec![Span::styled(
-            "♠ Press Enter to play ♠",
-            Style::default()
-                .bg(Color::LightGreen)
-                .fg(Color::Black)
-                .add_modifier(Modifier::BOLD)
-        )]),
-    ])
+        Spans::from(vec![Span::raw(format!("Pay table: {}", pay_table.name))]),
+        Spans::from(vec![Span::raw(rules.to_string())]),
+    ];
+
+    lines.extend(status.into_iter().map(|line| Spans::from(vec![Span::raw(line)])));
+    lines.push(Spans::from(vec![Span::raw("")]));
+
+    if !high_scores.is_empty() {
+        lines.extend(high_scores.into_iter().map(|line| Spans::from(vec![Span::raw(line)])));
+        lines.push(Spans::from(vec![Span::raw("")]));
+    }
+
+    lines.push(Spans::from(vec![Span::styled(
+        prompt,
+        Style::default()
+            .bg(Color::LightGreen)
+            .fg(Color::Black)
+            .add_modifier(Modifier::BOLD)
+    )]));
+
+    let welcome = Paragraph::new(lines)
     .alignment(Alignment::Center)
     .block(
         Block::default()
@@ -466,64 +1593,307 @@ fn render_welcome<'a>() -> Paragraph<'a> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use single_player_poker::game::rules::Variant;
+    use single_player_poker::parse_hand;
+
+    #[test]
+    fn pay_table_standard_rows() {
+        let rows = pay_table_rows(&PayTable::standard(), &Rules::default(), None);
+
+        assert_eq!(9, rows.len());
+        assert_eq!(HandCategory::RoyalFlush, rows[0].0);
+        assert!(rows[0].1.starts_with("Royal Flush"));
+        assert!(rows[0].1.ends_with("40"));
+        assert_eq!(HandCategory::Pair, rows[8].0);
+    }
+
+    #[test]
+    fn pay_table_short_deck_rows() {
+        let rules = Rules {
+            variant: Variant::ShortDeck,
+            ..Rules::default()
+        };
+        let mut counts = [0; HandCategory::ALL.len()];
+        counts[HandCategory::Flush as usize] = 1;
+        counts[HandCategory::HighCard as usize] = 784;
+        let rows = pay_table_rows(&PayTable::short_deck(), &rules, Some(&counts));
+
+        // Flushes are listed above full houses
+        assert_eq!(HandCategory::Flush, rows[3].0);
+        assert_eq!(HandCategory::FullHouse, rows[4].0);
+        assert!(rows[3].1.ends_with("1/785"));
+        assert!(rows[4].1.ends_with('-'));
+    }
+
+    #[test]
+    fn pay_table_min_pair_row() {
+        let rows = pay_table_rows(&PayTable::jacks_or_better(), &Rules::default(), None);
+
+        assert!(rows[8].1.starts_with("Jacks or Better"));
+    }
+
+    #[test]
+    fn pay_table_wild_rows() {
+        let rules = Rules {
+            jokers: 0,
+            wild_rank: Some(Rank::Two),
+            ..Rules::default()
+        };
+        let rows = pay_table_rows(&PayTable::deuces_wild(), &rules, None);
+
+        assert_eq!(HandCategory::WildRoyalFlush, rows[1].0);
+        assert_eq!(HandCategory::FiveOfAKind, rows[2].0);
+        assert_eq!(HandCategory::ThreeOfAKind, rows[rows.len() - 1].0);
+    }
+
+    #[test]
+    fn daily_statuses() {
+        let today = Date::new(2024, 3, 9).unwrap();
+        let result = |hands| DailyResult { date: today, score: 12, hands };
+
+        assert_eq!("Daily challenge 2024-03-09: 10 hands", daily_status(today, None));
+        assert_eq!(
+            "Daily challenge 2024-03-09 done: 12 points, come back tomorrow",
+            daily_status(today, Some(&result(10)))
+        );
+        assert_eq!(
+            "Daily challenge 2024-03-09 stopped after 4 of 10 hands with 12 points",
+            daily_status(today, Some(&result(4)))
+        );
+    }
+
+    #[test]
+    fn saved_statuses() {
+        let mut snapshot = GameState::new(Rules::default(), PayTable::jacks_or_better(), 1).snapshot();
+        snapshot.score = 12;
+        snapshot.hands_played = 1;
+        assert_eq!("Saved game: 12 points after 1 hand (Jacks or Better 9/6)", saved_status(&snapshot));
+
+        let (state, _) = GameState::new(Rules::default(), PayTable::standard(), 1)
+            .update(Action::Deal)
+            .unwrap();
+        assert_eq!(
+            "Saved game: 0 points after 0 hands, hand in play (Standard)",
+            saved_status(&state.snapshot())
+        );
+    }
+
+    #[test]
+    fn high_score_tables() {
+        let rules = Rules::default();
+        let table = PayTable::standard();
+        let entry = |name: &str, mode, score| HighScore {
+            name: name.to_string(),
+            date: Date::new(2024, 3, 9).unwrap(),
+            mode,
+            pay_table: table.name.clone(),
+            pay_table_id: table.id(),
+            rules: rules.to_string(),
+            hands: 10,
+            score,
+        };
+        let mut scores = HighScores::default();
+        assert!(high_score_lines(&scores, Mode::Classic, &table, &rules).is_empty());
+
+        scores.record(entry("Ana", Mode::Classic, 40));
+        scores.record(entry("Bo", Mode::Daily, 12));
+        scores.record(entry("Cy", Mode::Daily, 20));
+        // Other pay tables are left out, even with the same name
+        let other = PayTable::deuces_wild().id();
+        scores.record(HighScore { pay_table_id: other, ..entry("Di", Mode::Daily, 99) });
+
+        assert_eq!(
+            vec![
+                "High scores, daily",
+                "1. Cy 20 in 10 hands, 2024-03-09",
+                "2. Bo 12 in 10 hands, 2024-03-09",
+                "High scores, classic",
+                "1. Ana 40 in 10 hands, 2024-03-09",
+            ],
+            high_score_lines(&scores, Mode::Daily, &table, &rules)
+        );
+    }
+
+    #[test]
+    fn session_modes() {
+        let mut config = Config::default();
+        assert_eq!(Mode::Classic, session_mode(&config, false));
+
+        config.trainer = true;
+        assert_eq!(Mode::Trainer, session_mode(&config, false));
+        assert_eq!(Mode::Autoplay, session_mode(&config, true));
+
+        config.daily = true;
+        assert_eq!(Mode::Daily, session_mode(&config, false));
+    }
+
+    #[test]
+    fn stats_tables() {
+        let rules = Rules::default();
+        let table = PayTable::standard();
+        let mut stats = LifetimeStats::default();
+        for s in ["2c 7d 9h Js Kc", "Ts Th 4c 8d Ac", "Ts Th 4c 4d Ac", "2c 8d 9h Js Kc"] {
+            let hand = parse_hand(s).unwrap();
+            stats.record(&table, &rules, &hand, rules.evaluate(hand[..].try_into().unwrap()));
+        }
+        let stats = stats.get(&table, &rules).unwrap();
+
+        assert_eq!(
+            vec![
+                "Hands played: 4",
+                "Average points per hand: 1.000",
+                "Best hand: Two Pair (Ts Th 4c 4d Ac)",
+                "Longest dry streak: 1 (current: 1)",
+            ],
+            stats_summary(stats, &rules)
+        );
+
+        let rows = stats_rows(stats, &rules, None);
+        assert_eq!(HandCategory::RoyalFlush, rows[0].0);
+        let high_card = format!("{:<18}{:>8}{:>11}{:>12}", "High Card", 2, "50.00%", "-");
+        assert_eq!((HandCategory::HighCard, high_card), rows[rows.len() - 1]);
+
+        let mut counts = [0; HandCategory::ALL.len()];
+        counts[HandCategory::HighCard as usize] = 3;
+        counts[HandCategory::Pair as usize] = 1;
+        let rows = stats_rows(stats, &rules, Some(&counts));
+        assert!(rows[rows.len() - 1].1.ends_with("75.0000%"));
+    }
+
+    #[test]
+    fn advice_in_background() {
+        let state = GameState::new(Rules::default(), PayTable::jacks_or_better(), 42);
+        let (state, _) = state.update(Action::Deal).unwrap();
+        let (tx, rx) = mpsc::channel();
+        spawn_advice(&tx, HandJob::new(7, &state));
+
+        match rx.recv_timeout(Duration::from_secs(30)).unwrap() {
+            Event::Advice(id, choices) => {
+                assert_eq!(7, id);
+                // Up to 3 of the 5 cards can be changed
+                assert_eq!(26, choices.unwrap().len());
+            }
+            _ => panic!("expected the advice"),
+        }
+    }
+
+    #[test]
+    fn draw_odds_in_background() {
+        let state = GameState::new(Rules::default(), PayTable::jacks_or_better(), 42);
+        let (state, _) = state.update(Action::Deal).unwrap();
+        let (tx, rx) = mpsc::channel();
+        let odds_tx = spawn_draw_odds_worker(&tx);
+        odds_tx.send((HandJob::new(3, &state), vec![0, 2])).unwrap();
+
+        match rx.recv_timeout(Duration::from_secs(30)).unwrap() {
+            Event::DrawOdds(id, outcomes) => {
+                assert_eq!(3, id);
+                let outcomes = outcomes.unwrap();
+                assert_eq!(vec![0, 2], outcomes.to_change);
+                assert_eq!(47 * 46 / 2, outcomes.draws());
+            }
+            _ => panic!("expected the draw odds"),
+        }
+    }
+
+    #[test]
+    fn choice_strings() {
+        let hand = single_player_poker::parse_hand("Ah Kh Qh Jh 2c").unwrap();
+
+        assert_eq!("Ah Kh Qh Jh 2c", choice_string(&hand, &[]));
+        assert_eq!("-- Kh Qh -- --", choice_string(&hand, &[0, 3, 4]));
+    }
+
+    #[test]
+    fn draw_odds_standard_rows() {
+        let hand = single_player_poker::parse_hand("Ah Kh Qh Jh 2c").unwrap();
+        let deck: Vec<Card> = Rules::default()
+            .new_deck()
+            .remaining()
+            .iter()
+            .filter(|c| !hand.contains(c))
+            .copied()
+            .collect();
+        let outcomes =
+            advisor::outcomes(&hand, &[4], &deck, &Rules::default(), &PayTable::standard()).unwrap();
+        let rows = draw_odds_rows(&outcomes, &Rules::default());
+
+        // Wild hands are left out
+        assert_eq!(10, rows.len());
+        assert_eq!(HandCategory::RoyalFlush, rows[0].0);
+        assert!(rows[0].1.ends_with("2.13%"));
+        assert!(rows[9].1.starts_with("High Card"));
+    }
 
     #[test]
     fn display_nothing() {
-        let (_par, poker_hand) = render_message(&0);
+        let (_par, poker_hand) = render_message(HandCategory::HighCard, 0);
         assert_eq!("Nothing!", poker_hand);
     }
 
     #[test]
     fn display_pair() {
-        let (_par, poker_hand) = render_message(&1);
+        let (_par, poker_hand) = render_message(HandCategory::Pair, 1);
         assert_eq!("Pair!", poker_hand);
     }
 
     #[test]
     fn display_two_pair() {
-        let (_par, poker_hand) = render_message(&3);
+        let (_par, poker_hand) = render_message(HandCategory::TwoPair, 3);
         assert_eq!("Two Pair!", poker_hand);
     }
 
     #[test]
     fn display_three_of_a_kind() {
-        let (_par, poker_hand) = render_message(&5);
+        let (_par, poker_hand) = render_message(HandCategory::ThreeOfAKind, 5);
         assert_eq!("Three of a kind!", poker_hand);
     }
 
     #[test]
     fn display_straight() {
-        let (_par, poker_hand) = render_message(&10);
+        let (_par, poker_hand) = render_message(HandCategory::Straight, 10);
         assert_eq!("Straight!", poker_hand);
     }
 
     #[test]
     fn display_flush() {
-        let (_par, poker_hand) = render_message(&15);
+        let (_par, poker_hand) = render_message(HandCategory::Flush, 15);
         assert_eq!("Flush!", poker_hand);
     }
 
     #[test]
     fn display_full_house() {
-        let (_par, poker_hand) = render_message(&18);
+        let (_par, poker_hand) = render_message(HandCategory::FullHouse, 18);
         assert_eq!("Full House!", poker_hand);
     }
 
     #[test]
     fn display_four_of_a_kind() {
-        let (_par, poker_hand) = render_message(&20);
+        let (_par, poker_hand) = render_message(HandCategory::FourOfAKind, 20);
         assert_eq!("Four of a kind!", poker_hand);
     }
 
     #[test]
     fn display_straight_flush() {
-        let (_par, poker_hand) = render_message(&30);
+        let (_par, poker_hand) = render_message(HandCategory::StraightFlush, 30);
         assert_eq!("Straight Flush!", poker_hand);
     }
 
+    #[test]
+    fn display_five_of_a_kind() {
+        let (_par, poker_hand) = render_message(HandCategory::FiveOfAKind, 35);
+        assert_eq!("Five of a kind!", poker_hand);
+    }
+
+    #[test]
+    fn display_wild_royal_flush() {
+        let (_par, poker_hand) = render_message(HandCategory::WildRoyalFlush, 40);
+        assert_eq!("Wild Royal Flush!", poker_hand);
+    }
+
     #[test]
     fn display_royal_flush() {
-        let (_par, poker_hand) = render_message(&40);
+        let (_par, poker_hand) = render_message(HandCategory::RoyalFlush, 40);
         assert_eq!("Royal Flush!", poker_hand);
     }
 }